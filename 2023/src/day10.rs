@@ -1,14 +1,10 @@
-use {
-    aoc_core::{
-        basic_grid,
-        grid::{Grid, Position},
-        Solution,
-    },
-    std::{cmp::Ordering, collections::HashSet},
+use aoc_core::{
+    basic_grid,
+    geometry::{self, Point},
+    grid::{Grid, Position},
+    Solution,
 };
 
-const CORNERS: [u8; 4] = [b'7', b'J', b'L', b'F'];
-
 pub struct Day10;
 
 impl Solution<'_> for Day10 {
@@ -40,22 +36,15 @@ impl Solution<'_> for Day10 {
 
     fn part_2(input: Self::Input) -> Self::Output2 {
         let start = input.index_of(&b'S').expect("Has starting point");
-        let [a, b] = interpret_start(start, &input);
+        let [a, _] = interpret_start(start, &input);
         let path = follow_path(start, a, &input);
-        let path_set = path.iter().copied().collect();
-        let mut input = input;
-        // Set the 'S' space to be the right pipe
-        for pipe in CORNERS {
-            input[start] = pipe;
-            let neighbors = connections(start, &input);
-            if neighbors == [a, b] || neighbors == [b, a] {
-                break;
-            }
-        }
-        input
-            .index_range()
-            .filter(|x| winding_number(*x, &path, &path_set, &input) != 0)
-            .count()
+        // The loop is a polygon with a vertex at every tile it passes through,
+        // so the enclosed tiles are exactly the lattice points inside it.
+        let polygon: Vec<Point> = path
+            .into_iter()
+            .map(|(i, j)| Point::new(i as i64, j as i64))
+            .collect();
+        geometry::interior_points(&polygon) as usize
     }
 }
 
@@ -72,53 +61,6 @@ fn follow_path(start: Position, first: Position, map: &Grid<u8>) -> Vec<Position
     result
 }
 
-// See https://en.wikipedia.org/wiki/Point_in_polygon#Winding_number_algorithm
-fn winding_number(
-    x: Position,
-    path: &[Position],
-    path_set: &HashSet<Position>,
-    map: &Grid<u8>,
-) -> isize {
-    if path_set.contains(&x) {
-        return 0;
-    }
-
-    let n = path.len();
-    let i = x.0;
-    let mut prev_corner_parity: Option<isize> = None;
-    let mut total = 0;
-    for j in (x.1 + 1)..(map.n_cols()) {
-        let mut y = (i, j);
-        if path_set.contains(&y) {
-            let pipe = map[y];
-            let q = path.iter().position(|z| z == &y).unwrap();
-            let q = (q + 1) % n;
-            let z = path[q];
-            if CORNERS.contains(&pipe) {
-                y = path[(q + n - 2) % n];
-            }
-            let parity = match z.0.cmp(&y.0) {
-                Ordering::Greater => 1,
-                Ordering::Less => -1,
-                Ordering::Equal => 0,
-            };
-            if pipe == b'|' {
-                prev_corner_parity = None;
-            } else if CORNERS.contains(&pipe) {
-                // If we hit two corners with the same parity in a row
-                // they only count once (e.g. F--J only counts as a single crossing).
-                if prev_corner_parity == Some(parity) {
-                    prev_corner_parity = None;
-                    continue;
-                }
-                prev_corner_parity = Some(parity);
-            }
-            total += parity;
-        }
-    }
-    total
-}
-
 fn next_position(prev_x: Position, x: Position, map: &Grid<u8>) -> Position {
     let [a, b] = connections(x, map);
     if a != prev_x {
@@ -2,6 +2,7 @@ use {
     aoc_core::{
         abs_diff, basic_grid,
         grid::{Grid, Position},
+        iter::pairs,
         min_max, Solution,
     },
     std::collections::HashSet,
@@ -21,13 +22,9 @@ impl Solution<'_> for Day11 {
     fn part_1(input: Self::Input) -> Self::Output1 {
         let map = explicit_expand_space(input);
         let galaxies: Vec<Position> = map.index_range().filter(|x| map[*x] == b'#').collect();
-        let mut total = 0;
-        for (i, &(x, y)) in galaxies.iter().enumerate() {
-            for &(u, v) in &galaxies[(i + 1)..] {
-                total += abs_diff(x, u) + abs_diff(y, v);
-            }
-        }
-        total
+        pairs(&galaxies)
+            .map(|(&(x, y), &(u, v))| abs_diff(x, u) + abs_diff(y, v))
+            .sum()
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
@@ -41,14 +38,12 @@ fn part2_solution(input: Grid<u8>, expansion_factor: usize) -> usize {
     let galaxies: Vec<Position> = input.index_range().filter(|x| input[*x] == b'#').collect();
 
     let mut total = 0;
-    for (i, &(x, y)) in galaxies.iter().enumerate() {
-        for &(u, v) in &galaxies[(i + 1)..] {
-            let (row_start, row_end) = min_max(x, u);
-            let (col_start, col_end) = min_max(y, v);
+    for (&(x, y), &(u, v)) in pairs(&galaxies) {
+        let (row_start, row_end) = min_max(x, u);
+        let (col_start, col_end) = min_max(y, v);
 
-            total += traverse(row_start, row_end, expansion_factor, &empty_rows);
-            total += traverse(col_start, col_end, expansion_factor, &empty_cols);
-        }
+        total += traverse(row_start, row_end, expansion_factor, &empty_rows);
+        total += traverse(col_start, col_end, expansion_factor, &empty_cols);
     }
     total
 }
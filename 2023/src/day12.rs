@@ -1,6 +1,6 @@
 use {
-    aoc_core::Solution,
-    std::{cmp::Ordering, collections::HashMap},
+    aoc_core::{memo::Memo, Solution},
+    std::cmp::Ordering,
 };
 
 pub struct Day12;
@@ -26,17 +26,15 @@ impl Solution<'_> for Day12 {
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let mut cache = HashMap::new();
+        let mut memo = Memo::new();
         input
             .into_iter()
-            .map(|(spring_data, contiguous_data)| {
-                count_configurations(spring_data, contiguous_data, &mut cache)
-            })
+            .map(|key| memo.call(key, count_configurations))
             .sum()
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let mut cache = HashMap::new();
+        let mut memo = Memo::new();
         input
             .iter()
             .map(|(spring_data, contiguous_data)| {
@@ -46,23 +44,21 @@ impl Solution<'_> for Day12 {
                     .flatten()
                     .copied()
                     .collect();
-                count_configurations(unfolded_spring_data, unfolded_contiguous_data, &mut cache)
+                memo.call(
+                    (unfolded_spring_data, unfolded_contiguous_data),
+                    count_configurations,
+                )
             })
             .sum()
     }
 }
 
+type Key = (SpringData, ContiguousData);
+
 fn count_configurations(
-    mut spring_data: SpringData,
-    mut contiguous_data: ContiguousData,
-    cache: &mut HashMap<(SpringData, ContiguousData), u64>,
+    memo: &mut Memo<Key, u64>,
+    (mut spring_data, mut contiguous_data): Key,
 ) -> u64 {
-    let cache_key = (spring_data.clone(), contiguous_data.clone());
-
-    if let Some(result) = cache.get(&cache_key) {
-        return *result;
-    }
-
     if contiguous_data.is_empty() {
         if spring_data.contains(&SpringDatum::Broken) {
             return 0;
@@ -71,23 +67,24 @@ fn count_configurations(
         }
     }
 
-    let result = match spring_data.pop() {
+    match spring_data.pop() {
         None => 0,
-        Some(SpringDatum::Operational) => count_configurations(spring_data, contiguous_data, cache),
+        Some(SpringDatum::Operational) => {
+            memo.call((spring_data, contiguous_data), count_configurations)
+        }
         Some(SpringDatum::Unknown) => {
             let operational = spring_data.clone();
 
             let mut broken = spring_data;
             broken.push(SpringDatum::Broken);
 
-            let a = count_configurations(operational, contiguous_data.clone(), cache);
-            let b = count_configurations(broken, contiguous_data, cache);
+            let a = memo.call((operational, contiguous_data.clone()), count_configurations);
+            let b = memo.call((broken, contiguous_data), count_configurations);
 
             a + b
         }
         Some(SpringDatum::Broken) => {
             let Some(goal_length) = contiguous_data.pop() else {
-                cache.insert(cache_key, 0);
                 return 0;
             };
             let mut count = 1;
@@ -99,28 +96,23 @@ fn count_configurations(
                 Ordering::Greater => 0,
                 Ordering::Equal => {
                     spring_data.pop();
-                    count_configurations(spring_data, contiguous_data, cache)
+                    memo.call((spring_data, contiguous_data), count_configurations)
                 }
                 Ordering::Less => {
                     for _ in 0..(goal_length - count) {
                         if let Some(SpringDatum::Operational) | None = spring_data.pop() {
-                            cache.insert(cache_key, 0);
                             return 0;
                         }
                     }
                     if let Some(SpringDatum::Broken) = spring_data.last() {
-                        cache.insert(cache_key, 0);
                         return 0;
                     }
                     spring_data.pop();
-                    count_configurations(spring_data, contiguous_data, cache)
+                    memo.call((spring_data, contiguous_data), count_configurations)
                 }
             }
         }
-    };
-
-    cache.insert(cache_key, result);
-    result
+    }
 }
 
 pub type SpringData = Vec<SpringDatum>;
@@ -1,11 +1,9 @@
-use {
-    aoc_core::{
-        basic_grid,
-        grid::{Grid, NeighborsCreator, Position},
-        iter::AtMost,
-        Solution,
-    },
-    std::collections::HashSet,
+use aoc_core::{
+    basic_grid,
+    bitset::VisitedGrid,
+    grid::{Grid, NeighborsCreator, Position},
+    iter::AtMost,
+    Solution,
 };
 
 pub struct Day16;
@@ -20,8 +18,7 @@ impl Solution<'_> for Day16 {
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let visited = follow_beam(BeamState::new((0, 0), Direction::Right), &input);
-        visited.len()
+        follow_beam(BeamState::new((0, 0), Direction::Right), &input)
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
@@ -35,22 +32,23 @@ impl Solution<'_> for Day16 {
         top.chain(left)
             .chain(bottom)
             .chain(right)
-            .map(|initial_state| follow_beam(initial_state, &input).len())
+            .map(|initial_state| follow_beam(initial_state, &input))
             .max()
             .unwrap()
     }
 }
 
-fn follow_beam(initial_state: BeamState, grid: &Grid<u8>) -> HashSet<Position> {
-    let mut visited = HashSet::new();
+// Number of tiles the beam passes through.
+fn follow_beam(initial_state: BeamState, grid: &Grid<u8>) -> usize {
+    // One layer per direction.
+    let mut visited = VisitedGrid::for_grid(grid, 4);
     let mut stack = vec![initial_state];
     let nc = grid.neighbor_context();
 
     while let Some(state) = stack.pop() {
-        if visited.contains(&state) {
+        if !visited.insert_layer(state.position, state.direction as usize) {
             continue;
         }
-        visited.insert(state);
 
         match grid[state.position] {
             b'.' => {
@@ -81,7 +79,7 @@ fn follow_beam(initial_state: BeamState, grid: &Grid<u8>) -> HashSet<Position> {
         }
     }
 
-    visited.into_iter().map(|state| state.position).collect()
+    visited.positions().count()
 }
 
 fn maybe_push<T>(xs: &mut Vec<T>, mx: Option<T>) {
@@ -90,7 +88,7 @@ fn maybe_push<T>(xs: &mut Vec<T>, mx: Option<T>) {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct BeamState {
     position: Position,
     direction: Direction,
@@ -105,7 +103,7 @@ impl BeamState {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     Left,
     Right,
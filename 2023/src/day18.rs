@@ -1,4 +1,7 @@
-use aoc_core::Solution;
+use aoc_core::{
+    geometry::{self, Point},
+    Solution,
+};
 
 pub struct Day18;
 
@@ -42,27 +45,15 @@ where
     I: IntoIterator<Item = (Direction, isize)>,
 {
     let mut vertices = Vec::new();
-    let mut current: (isize, isize) = (0, 0);
-    let mut perimeter = 0;
-    vertices.push(current);
+    let mut current = Point::new(0, 0);
     for (d, l) in instructions {
         let (di, dj) = d.delta();
-        current.0 += di * l;
-        current.1 += dj * l;
+        current += Point::new(di as i64, dj as i64) * (l as i64);
         vertices.push(current);
-        perimeter += l;
     }
 
-    // See https://en.wikipedia.org/wiki/Shoelace_formula
-    let mut area = 0;
-    for ((yi, xi), (yj, xj)) in vertices
-        .iter()
-        .copied()
-        .zip(vertices.iter().skip(1).copied())
-    {
-        area += (yi + yj) * (xi - xj)
-    }
-    (area.abs() / 2) + (perimeter / 2) + 1
+    // The trench is dug along the boundary, so it counts as well as the interior.
+    (geometry::interior_points(&vertices) + geometry::boundary_points(&vertices)) as isize
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1,6 +1,10 @@
 use {
-    aoc_core::{strip_label, Solution},
-    std::{collections::HashMap, ops::Range},
+    aoc_core::{
+        hyperbox::{self, Predicate},
+        parse::{alphanumeric, delimited, labelled, lines, one_of, unsigned, Parser},
+        Solution,
+    },
+    std::collections::HashMap,
 };
 
 pub struct Day19;
@@ -11,17 +15,12 @@ impl<'a> Solution<'a> for Day19 {
     type Output2 = usize;
 
     fn parse_input(data: &'a str) -> Self::Input {
-        let (rules, parts) = data.split_once("\n\n").expect("Blocks");
-
-        let rules = rules
-            .lines()
-            .map(|line| {
-                let rule = Rule::from_str(line);
-                (rule.name, rule)
-            })
-            .collect();
-        let parts = parts.lines().map(Part::from_str).collect();
-
+        let (rules, parts) = lines(Rule::parser())
+            .skip("\n\n")
+            .then(lines(Part::parser()))
+            .parse(data)
+            .expect("Valid workflows and parts");
+        let rules = rules.into_iter().map(|rule| (rule.name, rule)).collect();
         (rules, parts)
     }
 
@@ -61,34 +60,36 @@ fn evaluate_part<'a>(part: &Part, rules: &HashMap<&'a str, Rule<'a>>) -> Evaluat
 
 fn evaluate_range<'a>(rules: &HashMap<&'a str, Rule<'a>>) -> usize {
     let mut accepted = Vec::new();
-    let mut stack = vec![("in", PartRange::default())];
+    let mut stack = vec![("in", PartRange::from_corners([1; 4], [4000; 4]))];
+
+    let mut dispatch = |target: &'a str, range: PartRange, stack: &mut Vec<_>| {
+        if target == "A" {
+            accepted.push(range);
+        } else if target != "R" {
+            stack.push((target, range));
+        }
+    };
 
     while let Some((label, range)) = stack.pop() {
         let rule = &rules[label];
-        let mut fall_through = range;
+        let mut fall_through = Some(range);
         for (condition, target) in &rule.conditions {
-            let target = *target;
-            let split = fall_through.split(condition);
-            let new_range = split.0;
-            fall_through = split.1;
-            if target == "A" {
-                accepted.push(new_range);
-            } else if target != "R" {
-                stack.push((target, new_range));
+            let Some(current) = fall_through else {
+                break;
+            };
+            let (matching, rest) =
+                current.partition(condition.quantity.axis(), condition.predicate());
+            fall_through = rest;
+            if let Some(matching) = matching {
+                dispatch(target, matching, &mut stack);
             }
         }
-        let target = rule.fallback;
-        if target == "A" {
-            accepted.push(fall_through);
-        } else if target != "R" {
-            stack.push((target, fall_through));
+        if let Some(fall_through) = fall_through {
+            dispatch(rule.fallback, fall_through, &mut stack);
         }
     }
 
-    accepted
-        .into_iter()
-        .map(|r| r.x.len() * r.m.len() * r.a.len() * r.s.len())
-        .sum::<usize>()
+    accepted.into_iter().map(|r| r.volume() as usize).sum()
 }
 
 enum Evaluation {
@@ -104,31 +105,28 @@ pub struct Rule<'a> {
 }
 
 impl<'a> Rule<'a> {
-    fn from_str(data: &'a str) -> Self {
-        let (name, remainder) = data.split_once('{').unwrap();
-        let remainder = remainder.strip_suffix('}').unwrap();
-        let conditions = remainder
-            .split(',')
-            .filter_map(|rule| {
-                let (condition, target) = rule.split_once(':')?;
-                let mut iter = condition.bytes();
-                let quantity = Quantity::from_u8(iter.next().unwrap());
-                let operator = Operator::from_u8(iter.next().unwrap());
-                let value = condition[2..].parse().unwrap();
-                let condition = Condition {
-                    quantity,
-                    operator,
-                    value,
-                };
-                Some((condition, target))
+    // e.g. `px{a<2006:qkq,m>2090:A,rhg}`
+    fn parser() -> impl Parser<'a, Self> {
+        let condition = one_of(Quantity::NAMES)
+            .then(one_of(Operator::SYMBOLS))
+            .then(unsigned())
+            .map(|((quantity, operator), value)| Condition {
+                quantity,
+                operator,
+                value,
+            });
+        let branch = condition.skip(":").then(alphanumeric());
+        alphanumeric()
+            .then(delimited(
+                "{",
+                branch.skip(",").many().then(alphanumeric()),
+                "}",
+            ))
+            .map(|(name, (conditions, fallback))| Self {
+                name,
+                conditions,
+                fallback,
             })
-            .collect();
-        let fallback = remainder.split(',').next_back().unwrap();
-        Self {
-            name,
-            conditions,
-            fallback,
-        }
     }
 }
 
@@ -139,6 +137,15 @@ pub struct Condition {
     value: u64,
 }
 
+impl Condition {
+    fn predicate(&self) -> Predicate {
+        match self.operator {
+            Operator::Lt => Predicate::LessThan(self.value as i64),
+            Operator::Gt => Predicate::GreaterThan(self.value as i64),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Quantity {
     X,
@@ -148,13 +155,19 @@ pub enum Quantity {
 }
 
 impl Quantity {
-    fn from_u8(b: u8) -> Self {
-        match b {
-            b'x' => Self::X,
-            b'm' => Self::M,
-            b'a' => Self::A,
-            b's' => Self::S,
-            _ => panic!("Unknown quantity"),
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("x", Self::X),
+        ("m", Self::M),
+        ("a", Self::A),
+        ("s", Self::S),
+    ];
+
+    fn axis(&self) -> usize {
+        match self {
+            Self::X => 0,
+            Self::M => 1,
+            Self::A => 2,
+            Self::S => 3,
         }
     }
 }
@@ -166,89 +179,11 @@ pub enum Operator {
 }
 
 impl Operator {
-    fn from_u8(b: u8) -> Self {
-        match b {
-            b'<' => Self::Lt,
-            b'>' => Self::Gt,
-            _ => panic!("Unknown operator"),
-        }
-    }
+    const SYMBOLS: &'static [(&'static str, Self)] = &[("<", Self::Lt), (">", Self::Gt)];
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct PartRange {
-    x: Range<usize>,
-    m: Range<usize>,
-    a: Range<usize>,
-    s: Range<usize>,
-}
-
-impl PartRange {
-    fn split(&self, condition: &Condition) -> (Self, Self) {
-        use {Operator::*, Quantity::*};
-        let value = match condition.operator {
-            Lt => condition.value as usize,
-            Gt => (condition.value + 1) as usize,
-        };
-        let (lt, gt) = match condition.quantity {
-            X => (
-                Self {
-                    x: (self.x.start)..(value),
-                    ..self.clone()
-                },
-                Self {
-                    x: (value)..(self.x.end),
-                    ..self.clone()
-                },
-            ),
-            M => (
-                Self {
-                    m: (self.m.start)..(value),
-                    ..self.clone()
-                },
-                Self {
-                    m: (value)..(self.m.end),
-                    ..self.clone()
-                },
-            ),
-            A => (
-                Self {
-                    a: (self.a.start)..(value),
-                    ..self.clone()
-                },
-                Self {
-                    a: (value)..(self.a.end),
-                    ..self.clone()
-                },
-            ),
-            S => (
-                Self {
-                    s: (self.s.start)..(value),
-                    ..self.clone()
-                },
-                Self {
-                    s: (value)..(self.s.end),
-                    ..self.clone()
-                },
-            ),
-        };
-        match condition.operator {
-            Lt => (lt, gt),
-            Gt => (gt, lt),
-        }
-    }
-}
-
-impl Default for PartRange {
-    fn default() -> Self {
-        Self {
-            x: 1..4001,
-            m: 1..4001,
-            a: 1..4001,
-            s: 1..4001,
-        }
-    }
-}
+// Ranges of the x, m, a and s ratings, in that order.
+type PartRange = hyperbox::Box<4>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Part {
@@ -259,32 +194,26 @@ pub struct Part {
 }
 
 impl Part {
-    fn from_str(data: &str) -> Self {
-        let n = data.len();
-        let remainder = &data[1..(n - 1)];
-        let (x, remainder) = remainder.split_once(',').unwrap();
-        let (m, remainder) = remainder.split_once(',').unwrap();
-        let (a, s) = remainder.split_once(',').unwrap();
-        Self {
-            x: strip_label(x, '=').parse().unwrap(),
-            m: strip_label(m, '=').parse().unwrap(),
-            a: strip_label(a, '=').parse().unwrap(),
-            s: strip_label(s, '=').parse().unwrap(),
-        }
+    // e.g. `{x=787,m=2655,a=1222,s=2876}`
+    fn parser<'a>() -> impl Parser<'a, Self> {
+        let fields = labelled("x=", unsigned())
+            .skip(",")
+            .then(labelled("m=", unsigned()))
+            .skip(",")
+            .then(labelled("a=", unsigned()))
+            .skip(",")
+            .then(labelled("s=", unsigned()));
+        delimited("{", fields, "}").map(|(((x, m), a), s)| Self { x, m, a, s })
     }
 
     fn matches(&self, condition: &Condition) -> bool {
-        use {Operator::*, Quantity::*};
-        match (condition.quantity, condition.operator) {
-            (X, Lt) => self.x < condition.value,
-            (M, Lt) => self.m < condition.value,
-            (A, Lt) => self.a < condition.value,
-            (S, Lt) => self.s < condition.value,
-            (X, Gt) => self.x > condition.value,
-            (M, Gt) => self.m > condition.value,
-            (A, Gt) => self.a > condition.value,
-            (S, Gt) => self.s > condition.value,
-        }
+        let value = match condition.quantity {
+            Quantity::X => self.x,
+            Quantity::M => self.m,
+            Quantity::A => self.a,
+            Quantity::S => self.s,
+        };
+        condition.predicate().matches(value as i64)
     }
 }
 
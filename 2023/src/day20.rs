@@ -1,65 +1,77 @@
 use {
-    aoc_core::{lcm, Solution},
-    std::collections::{HashMap, VecDeque},
+    aoc_core::{
+        intern::{Id, IdMap, IdVec, Interner},
+        number_theory::first_alignment,
+        Solution,
+    },
+    std::collections::VecDeque,
 };
 
 pub struct Day20;
 
 impl<'a> Solution<'a> for Day20 {
-    type Input = (Broadcaster<'a>, HashMap<&'a str, Module<'a>>);
+    type Input = Network<'a>;
     type Output1 = usize;
     type Output2 = ();
 
     fn parse_input(data: &'a str) -> Self::Input {
-        let mut broadcaster = None;
-        let modules = data
+        let mut labels = Interner::new();
+        let broadcaster = labels.intern("broadcaster");
+        let defined: Vec<(Id, Module)> = data
             .lines()
-            .filter_map(|line| {
+            .map(|line| {
                 let (label, destinations) = line.split_once(" -> ").expect("Has arrow");
-                let destinations = destinations.split(',').map(|s| s.trim()).collect();
-
-                if label == "broadcaster" {
-                    assert!(broadcaster.is_none(), "Only one broadcaster");
-                    broadcaster = Some(Broadcaster { destinations });
-                    return None;
-                }
-
-                let (kind, label) = label
-                    .strip_prefix('%')
-                    .map(|s| (ModuleKind::FlipFlop, s))
-                    .unwrap_or_else(|| {
-                        (
-                            ModuleKind::Conjunction,
-                            label
-                                .strip_prefix('&')
-                                .expect("All modules are flip-flop or conjunction"),
-                        )
-                    });
-                let module = Module { destinations, kind };
-                Some((label, module))
+                let destinations = destinations
+                    .split(',')
+                    .map(|s| labels.intern(s.trim()))
+                    .collect();
+
+                let (kind, label) = if let Some(label) = label.strip_prefix('%') {
+                    (ModuleKind::FlipFlop, label)
+                } else if let Some(label) = label.strip_prefix('&') {
+                    (ModuleKind::Conjunction, label)
+                } else {
+                    assert_eq!(
+                        label, "broadcaster",
+                        "All modules are flip-flop or conjunction"
+                    );
+                    (ModuleKind::Broadcaster, label)
+                };
+                (labels.intern(label), Module { destinations, kind })
             })
             .collect();
-        (broadcaster.expect("Must have broadcaster"), modules)
+
+        // Labels which only appear as destinations, like `rx`, are outputs.
+        let output = Module {
+            destinations: Vec::new(),
+            kind: ModuleKind::Output,
+        };
+        let mut modules = IdVec::filled(labels.len(), output);
+        for (id, module) in defined {
+            modules[id] = module;
+        }
+        Network {
+            labels,
+            broadcaster,
+            modules,
+        }
     }
 
-    fn part_1(input: Self::Input) -> Self::Output1 {
-        let (broadcaster, modules) = input;
-        let default_state = construct_default_state(&modules);
+    fn part_1(network: Self::Input) -> Self::Output1 {
+        let default_state = network.default_state();
 
         let mut current_state = default_state.clone();
         let mut high_counts = Vec::new();
         let mut low_counts = Vec::new();
         let mut button_push_counts = 0;
         while button_push_counts < 1000 {
-            let (new_state, low_count, high_count) =
-                push_button(&current_state, &broadcaster, &modules);
+            let (low_count, high_count) = network.push_button(&mut current_state);
             low_counts.push(low_count);
             high_counts.push(high_count);
             button_push_counts += 1;
-            if new_state == default_state {
+            if current_state == default_state {
                 break;
             }
-            current_state = new_state;
         }
         let cycle_total_low = low_counts.iter().copied().sum::<usize>();
         let cycle_total_high = high_counts.iter().copied().sum::<usize>();
@@ -78,274 +90,208 @@ impl<'a> Solution<'a> for Day20 {
         total_low * total_high
     }
 
-    fn part_2(input: Self::Input) -> Self::Output2 {
-        let (broadcaster, modules) = input;
-        let default_state = construct_default_state(&modules);
+    fn part_2(network: Self::Input) -> Self::Output2 {
+        let default_state = network.default_state();
 
-        let Some(switches) = find_output_switches("rx", &modules, &default_state) else {
-            // There's no example to test this on. The given example does not have
-            // the "rx" output, so we just exit.
+        // There's no example to test this on. The given example does not have
+        // the "rx" output, so we just exit.
+        let Some((hub, sub_circuits)) = network
+            .labels
+            .get("rx")
+            .and_then(|rx| network.output_sub_circuits(rx, &default_state))
+        else {
             return;
         };
 
-        assert!(switches
-            .iter()
-            .all(|label| matches!(default_state.get(label), Some(ModuleState::FlipFlop(_)))));
-
-        // There are four special switches with asymmetric cycle times.
-        // These switches determine the cycle time for their whole sub-circuit.
-        let sub_circuit_cycles = switches.into_iter().filter_map(|label| {
-            let (on_cycle, off_cycle) =
-                count_cycle_time(label, &default_state, &broadcaster, &modules);
-            if on_cycle != off_cycle {
-                Some(on_cycle + off_cycle)
-            } else {
-                None
-            }
-        });
+        // Each sub-circuit sends a high pulse to the hub on the first press at
+        // `offset` and then, by assumption, every `period` presses after that.
+        // Both are measured rather than assuming the circuit fires at the end
+        // of each of its periods.
+        let sub_circuit_cycles: Vec<(u64, u64)> = network
+            .firing_presses(hub, &sub_circuits, 2, &default_state)
+            .into_iter()
+            .map(|presses| (presses[0], presses[1] - presses[0]))
+            .collect();
 
-        // All the sub-circuit cycles need to be aligned for the output
-        // to be a low pulse. Therefore the answer is the lcm of the sub-circuit cycle lengths.
-        let answer = sub_circuit_cycles.reduce(lcm).unwrap();
+        // All the sub-circuits need to fire on the same press for the output to be a low pulse.
+        let answer = first_alignment(&sub_circuit_cycles).expect("Sub-circuits align");
         println!("{answer}");
     }
 }
 
-fn count_cycle_time<'a>(
-    label: &'a str,
-    default_state: &SystemState<'a>,
-    broadcaster: &Broadcaster<'a>,
-    modules: &HashMap<&'a str, Module<'a>>,
-) -> (u64, u64) {
-    let mut current_state = default_state.clone();
-    let on_cycle = {
-        let mut count = 0;
-        while !current_state.get(&label).unwrap().as_flip_flop() {
-            let (new_state, _, _) = push_button(&current_state, broadcaster, modules);
-            count += 1;
-            current_state = new_state;
-        }
-        count
-    };
-    let off_cycle = {
-        let mut count = 0;
-        while current_state.get(&label).unwrap().as_flip_flop() {
-            let (new_state, _, _) = push_button(&current_state, broadcaster, modules);
-            count += 1;
-            current_state = new_state;
-        }
-        count
-    };
-    (on_cycle, off_cycle)
+// Give up on sub-circuits which have not fired often enough after this many presses.
+const MAX_PRESSES: u64 = 1 << 20;
+
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    labels: Interner<&'a str>,
+    broadcaster: Id,
+    modules: IdVec<Module>,
 }
 
-fn construct_default_state<'a>(modules: &HashMap<&'a str, Module<'a>>) -> SystemState<'a> {
-    let mut inputs_map: HashMap<&'a str, Vec<&'a str>> = HashMap::new();
-    for (source, module) in modules.iter() {
-        for dest in &module.destinations {
-            let entry = inputs_map.entry(*dest).or_default();
-            entry.push(*source);
+impl Network<'_> {
+    fn default_state(&self) -> SystemState {
+        let mut state: SystemState = self
+            .modules
+            .values()
+            .iter()
+            .map(|module| match module.kind {
+                ModuleKind::FlipFlop => ModuleState::FlipFlop(false),
+                ModuleKind::Conjunction => ModuleState::Conjunction(IdMap::new()),
+                ModuleKind::Broadcaster | ModuleKind::Output => ModuleState::Stateless,
+            })
+            .collect();
+        for (source, module) in self.modules.iter() {
+            for dest in &module.destinations {
+                if let ModuleState::Conjunction(inputs) = &mut state[*dest] {
+                    inputs.insert(source, PulseKind::Low);
+                }
+            }
         }
+        state
     }
 
-    modules
-        .iter()
-        .map(|(label, module)| {
-            let state = match module.kind {
-                ModuleKind::FlipFlop => ModuleState::flip_flop(),
-                ModuleKind::Conjunction => {
-                    ModuleState::conjunction(inputs_map[label].iter().copied())
-                }
-            };
-            (*label, state)
-        })
-        .collect()
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Module<'a> {
-    destinations: Vec<&'a str>,
-    kind: ModuleKind,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ModuleKind {
-    FlipFlop,
-    Conjunction,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Broadcaster<'a> {
-    destinations: Vec<&'a str>,
-}
-
-// In the system the output in connected to a single conjunction,
-// which takes input from few conjunctions, each of which take input
-// from several flip-flops. Therefore, to get a low pulse output
-// we need all those switches to turn on simultaneously.
-// This function finds the set of flip-flops we need to focus on.
-fn find_output_switches<'a>(
-    output_label: &'a str,
-    modules: &HashMap<&'a str, Module<'a>>,
-    default_state: &SystemState<'a>,
-) -> Option<Vec<&'a str>> {
-    fn to_inputs<'a, 'b, I>(
-        labels: I,
-        state: &'b SystemState<'a>,
-    ) -> impl Iterator<Item = &'b &'a str>
-    where
-        I: Iterator<Item = &'b &'a str>,
-    {
-        labels.flat_map(|label| {
-            state
-                .get(label)
-                .expect("Sub Con has state")
-                .as_conjunction()
-                .keys()
-        })
+    // In the system the output is connected to a single conjunction (the hub),
+    // which takes input from a few conjunctions, each the last module of an
+    // independent sub-circuit. To get a low pulse output we need all of the
+    // sub-circuits to send a high pulse to the hub on the same button press.
+    // Returns the hub and the last modules of the sub-circuits.
+    fn output_sub_circuits(
+        &self,
+        output: Id,
+        default_state: &SystemState,
+    ) -> Option<(Id, Vec<Id>)> {
+        let (hub, _) = self
+            .modules
+            .iter()
+            .find(|(_, module)| module.destinations == [output])?;
+        Some((hub, default_state[hub].as_conjunction().keys().collect()))
     }
 
-    let con = modules.iter().find_map(|(label, module)| {
-        if module.destinations == [output_label] {
-            Some(label)
-        } else {
-            None
+    // The first `n` presses (counted from 1) on which each source sends a high
+    // pulse to `to`. Panics if some source does not fire `n` times within
+    // `MAX_PRESSES` presses.
+    fn firing_presses(
+        &self,
+        to: Id,
+        sources: &[Id],
+        n: usize,
+        default_state: &SystemState,
+    ) -> Vec<Vec<u64>> {
+        let mut state = default_state.clone();
+        let mut presses: Vec<Vec<u64>> = vec![Vec::new(); sources.len()];
+        for press in 1..=MAX_PRESSES {
+            self.send_pulses(&mut state, |pulse| {
+                if pulse.to != to || pulse.kind != PulseKind::High {
+                    return;
+                }
+                if let Some(i) = sources.iter().position(|s| *s == pulse.from) {
+                    // A source can fire more than once in a single press.
+                    if presses[i].last() != Some(&press) {
+                        presses[i].push(press);
+                    }
+                }
+            });
+            if presses.iter().all(|p| p.len() >= n) {
+                return presses.into_iter().map(|p| p[..n].to_vec()).collect();
+            }
         }
-    })?;
-    let con_inputs = default_state
-        .get(con)
-        .expect("Con has state")
-        .as_conjunction()
-        .keys();
-    let result = to_inputs(to_inputs(con_inputs, default_state), default_state)
-        .copied()
-        .collect();
-    Some(result)
-}
+        panic!("Sub-circuits did not fire within {MAX_PRESSES} presses");
+    }
 
-fn push_button<'a>(
-    init_state: &SystemState<'a>,
-    broadcaster: &Broadcaster<'a>,
-    modules: &HashMap<&'a str, Module<'a>>,
-) -> (SystemState<'a>, usize, usize) {
-    let mut low_count = broadcaster.destinations.len() + 1;
-    let mut high_count = 0;
-
-    let mut state = init_state.clone();
-    let mut pulses = VecDeque::new();
-    for label in &broadcaster.destinations {
-        pulses.push_back(Pulse {
-            from: "broadcaster",
-            to: label,
-            kind: PulseKind::Low,
+    // Returns the number of low and high pulses sent.
+    fn push_button(&self, state: &mut SystemState) -> (usize, usize) {
+        let mut low_count = 0;
+        let mut high_count = 0;
+        self.send_pulses(state, |pulse| match pulse.kind {
+            PulseKind::Low => low_count += 1,
+            PulseKind::High => high_count += 1,
         });
+        (low_count, high_count)
     }
 
-    while let Some(pulse) = pulses.pop_front() {
-        let response_pulse = match state.get_mut(pulse.to) {
-            Some(ModuleState::FlipFlop(is_on)) => match pulse.kind {
-                PulseKind::High => None,
-                PulseKind::Low => {
-                    if *is_on {
-                        *is_on = false;
+    // Push the button, calling `on_pulse` with each pulse as it is received.
+    fn send_pulses<F: FnMut(&Pulse)>(&self, state: &mut SystemState, mut on_pulse: F) {
+        // The button is not a module, so its pulse comes from the broadcaster itself.
+        let mut pulses = VecDeque::from([Pulse {
+            from: self.broadcaster,
+            to: self.broadcaster,
+            kind: PulseKind::Low,
+        }]);
+        while let Some(pulse) = pulses.pop_front() {
+            on_pulse(&pulse);
+            let response_pulse = match &mut state[pulse.to] {
+                ModuleState::Stateless if pulse.to == self.broadcaster => Some(pulse.kind),
+                ModuleState::Stateless => None,
+                ModuleState::FlipFlop(is_on) => match pulse.kind {
+                    PulseKind::High => None,
+                    PulseKind::Low => {
+                        *is_on = !*is_on;
+                        Some(if *is_on {
+                            PulseKind::High
+                        } else {
+                            PulseKind::Low
+                        })
+                    }
+                },
+                ModuleState::Conjunction(input_states) => {
+                    input_states[pulse.from] = pulse.kind;
+                    if input_states.values().all(|k| matches!(k, PulseKind::High)) {
                         Some(PulseKind::Low)
                     } else {
-                        *is_on = true;
                         Some(PulseKind::High)
                     }
                 }
-            },
-            Some(ModuleState::Conjunction(input_states)) => {
-                *input_states.get_mut(pulse.from).expect("All inputs known") = pulse.kind;
-                match pulse.kind {
-                    PulseKind::Low => Some(PulseKind::High),
-                    PulseKind::High => {
-                        if input_states.values().all(|k| matches!(k, PulseKind::High)) {
-                            Some(PulseKind::Low)
-                        } else {
-                            Some(PulseKind::High)
-                        }
-                    }
-                }
-            }
-            None => {
-                assert!(pulse.to == "rx" || pulse.to == "output");
-                None
-            }
-        };
-        match response_pulse {
-            None => (),
-            Some(PulseKind::Low) => {
-                let destinations = &modules[pulse.to].destinations;
-                low_count += destinations.len();
-                for label in destinations {
-                    pulses.push_back(Pulse {
-                        from: pulse.to,
-                        to: label,
-                        kind: PulseKind::Low,
-                    });
-                }
-            }
-            Some(PulseKind::High) => {
-                let destinations = &modules[pulse.to].destinations;
-                high_count += destinations.len();
-                for label in destinations {
+            };
+            if let Some(kind) = response_pulse {
+                for to in &self.modules[pulse.to].destinations {
                     pulses.push_back(Pulse {
                         from: pulse.to,
-                        to: label,
-                        kind: PulseKind::High,
+                        to: *to,
+                        kind,
                     });
                 }
             }
         }
     }
-
-    (state, low_count, high_count)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Pulse<'a> {
-    from: &'a str,
-    to: &'a str,
+struct Module {
+    destinations: Vec<Id>,
+    kind: ModuleKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleKind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pulse {
+    from: Id,
+    to: Id,
     kind: PulseKind,
 }
 
-type SystemState<'a> = HashMap<&'a str, ModuleState<'a>>;
+type SystemState = IdVec<ModuleState>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum ModuleState<'a> {
+enum ModuleState {
+    Stateless,
     FlipFlop(bool),
-    Conjunction(HashMap<&'a str, PulseKind>),
+    // The last pulse received from each input.
+    Conjunction(IdMap<PulseKind>),
 }
 
-impl<'a> ModuleState<'a> {
-    fn flip_flop() -> Self {
-        Self::FlipFlop(false)
-    }
-
-    fn conjunction<I>(inputs: I) -> Self
-    where
-        I: IntoIterator<Item = &'a str>,
-    {
-        Self::Conjunction(
-            inputs
-                .into_iter()
-                .map(|label| (label, PulseKind::Low))
-                .collect(),
-        )
-    }
-
-    fn as_conjunction(&self) -> &HashMap<&'a str, PulseKind> {
+impl ModuleState {
+    fn as_conjunction(&self) -> &IdMap<PulseKind> {
         match self {
             Self::Conjunction(inputs) => inputs,
-            Self::FlipFlop(_) => panic!("Should be conjunction"),
-        }
-    }
-
-    fn as_flip_flop(&self) -> bool {
-        match self {
-            Self::FlipFlop(inner) => *inner,
-            Self::Conjunction(_) => panic!("Should be flip-flop"),
+            _ => panic!("Should be conjunction"),
         }
     }
 }
@@ -2,6 +2,7 @@ use {
     aoc_core::{
         basic_grid,
         grid::{Grid, Position},
+        poly::Differences,
         Solution,
     },
     std::collections::HashSet,
@@ -40,15 +41,12 @@ impl Solution<'_> for Day21 {
 
         // Quadratic interpolation
         let ns = (0..3).map(|x| x * length_scale + offset);
-        let ys: Vec<usize> = ns
-            .map(|n| reachable_plots(&input, start, n as u64))
+        let ys: Vec<i64> = ns
+            .map(|n| reachable_plots(&input, start, n as u64) as i64)
             .collect();
-        let c = ys[0];
-        let a = ((ys[2] - c) - 2 * (ys[1] - c)) / 2;
-        let b = ys[1] - a - c;
 
         let x = (target - offset) / length_scale;
-        a * x * x + b * x + c
+        Differences::from_samples(&ys).extrapolate(x as i64) as usize
     }
 }
 
@@ -1,16 +1,15 @@
 use {
-    aoc_core::Solution,
+    aoc_core::{hyperbox, vector::Vec3, Solution},
     std::{
         cmp::Reverse,
         collections::{HashMap, HashSet},
-        ops::RangeInclusive,
     },
 };
 
 pub struct Day22;
 
 impl Solution<'_> for Day22 {
-    type Input = Vec<(Triple, Triple)>;
+    type Input = Vec<(Vec3<usize>, Vec3<usize>)>;
     type Output1 = usize;
     type Output2 = usize;
 
@@ -18,13 +17,19 @@ impl Solution<'_> for Day22 {
         data.lines()
             .map(|line| {
                 let (left, right) = line.split_once('~').expect("Has tilde");
-                (Triple::from_str(left), Triple::from_str(right))
+                (
+                    left.parse().expect("Bricks contain numbers"),
+                    right.parse().expect("Bricks contain numbers"),
+                )
             })
             .collect()
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let mut bricks: Vec<Brick> = input.into_iter().map(Into::into).collect();
+        let mut bricks: Vec<Brick> = input
+            .into_iter()
+            .map(|(a, b)| brick_from_ends(a, b))
+            .collect();
         let lowered_bricks = lower_bricks(&mut bricks);
         let (supporters, _) = compute_support_maps(&lowered_bricks);
         let unremovable = find_single_supporters(&supporters);
@@ -32,7 +37,10 @@ impl Solution<'_> for Day22 {
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let mut bricks: Vec<Brick> = input.into_iter().map(Into::into).collect();
+        let mut bricks: Vec<Brick> = input
+            .into_iter()
+            .map(|(a, b)| brick_from_ends(a, b))
+            .collect();
         let lowered_bricks = lower_bricks(&mut bricks);
         let (supporters, supporting) = compute_support_maps(&lowered_bricks);
         let single_supporters = find_single_supporters(&supporters);
@@ -72,10 +80,8 @@ fn find_single_supporters(supporters: &HashMap<Brick, Vec<Brick>>) -> HashSet<&B
 fn compute_support_maps(
     lowered_bricks: &[Brick],
 ) -> (HashMap<Brick, Vec<Brick>>, HashMap<Brick, Vec<Brick>>) {
-    let mut supporters: HashMap<Brick, Vec<Brick>> = lowered_bricks
-        .iter()
-        .map(|b| (b.clone(), Vec::new()))
-        .collect();
+    let mut supporters: HashMap<Brick, Vec<Brick>> =
+        lowered_bricks.iter().map(|b| (*b, Vec::new())).collect();
     let mut supporting = supporters.clone();
     for a in lowered_bricks {
         for b in lowered_bricks {
@@ -83,8 +89,8 @@ fn compute_support_maps(
                 continue;
             }
             if supports(a, b) {
-                supporters.get_mut(b).unwrap().push(a.clone());
-                supporting.get_mut(a).unwrap().push(b.clone());
+                supporters.get_mut(b).unwrap().push(*a);
+                supporting.get_mut(a).unwrap().push(*b);
             }
         }
     }
@@ -112,154 +118,41 @@ fn lower_bricks(bricks: &mut Vec<Brick>) -> Vec<Brick> {
 
 fn supports(a: &Brick, b: &Brick) -> bool {
     // a supports b if `collides(a, b.lower(1))`
-    let mut b = b.clone();
+    let mut b = *b;
     b.lower(1);
     collides(a, &b)
 }
 
 fn collides(a: &Brick, b: &Brick) -> bool {
-    overlap(a.x(), b.x()) && overlap(a.y(), b.y()) && overlap(a.z(), b.z())
+    a.intersects(b)
 }
 
-fn overlap(a: RangeInclusive<usize>, b: RangeInclusive<usize>) -> bool {
-    if a.start() < b.start() {
-        b.start() <= a.end()
-    } else {
-        a.start() <= b.end()
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+// The cubes a brick occupies, with axes x, y and z in that order.
+type Brick = hyperbox::Box<3>;
 
-enum Brick {
-    Singleton(Triple),
-    X {
-        x: RangeInclusive<usize>,
-        y: usize,
-        z: usize,
-    },
-    Y {
-        x: usize,
-        y: RangeInclusive<usize>,
-        z: usize,
-    },
-    Z {
-        x: usize,
-        y: usize,
-        z: RangeInclusive<usize>,
-    },
+trait Falling {
+    fn min_z(&self) -> usize;
+    fn lower(&mut self, amount: usize);
+    fn raise(&mut self, amount: usize);
 }
 
-impl Brick {
-    fn x(&self) -> RangeInclusive<usize> {
-        match self {
-            Self::Singleton(t) => (t.x)..=(t.x),
-            Self::X { x, .. } => x.clone(),
-            Self::Y { x, .. } | Self::Z { x, .. } => (*x)..=(*x),
-        }
-    }
-
-    fn y(&self) -> RangeInclusive<usize> {
-        match self {
-            Self::Singleton(t) => (t.y)..=(t.y),
-            Self::Y { y, .. } => y.clone(),
-            Self::X { y, .. } | Self::Z { y, .. } => (*y)..=(*y),
-        }
-    }
-
-    fn z(&self) -> RangeInclusive<usize> {
-        match self {
-            Self::Singleton(t) => (t.z)..=(t.z),
-            Self::Z { z, .. } => z.clone(),
-            Self::X { z, .. } | Self::Y { z, .. } => (*z)..=(*z),
-        }
-    }
-
+impl Falling for Brick {
     fn min_z(&self) -> usize {
-        match self {
-            Self::Singleton(t) => t.z,
-            Self::X { z, .. } | Self::Y { z, .. } => *z,
-            Self::Z { z, .. } => *z.start(),
-        }
+        self.start(2) as usize
     }
 
     fn lower(&mut self, amount: usize) {
-        match self {
-            Brick::Singleton(triple) => triple.z -= amount,
-            Brick::X { z, .. } | Brick::Y { z, .. } => *z -= amount,
-            Brick::Z { z, .. } => {
-                let start = *z.start();
-                let end = *z.end();
-                *z = (start - amount)..=(end - amount);
-            }
-        }
+        *self = self.translate(2, -(amount as i64));
     }
 
     fn raise(&mut self, amount: usize) {
-        match self {
-            Brick::Singleton(triple) => triple.z += amount,
-            Brick::X { z, .. } | Brick::Y { z, .. } => *z += amount,
-            Brick::Z { z, .. } => {
-                let start = *z.start();
-                let end = *z.end();
-                *z = (start + amount)..=(end + amount);
-            }
-        }
+        *self = self.translate(2, amount as i64);
     }
 }
 
-impl From<(Triple, Triple)> for Brick {
-    fn from(value: (Triple, Triple)) -> Self {
-        let (a, b) = if value.0 < value.1 {
-            (value.0, value.1)
-        } else {
-            (value.1, value.0)
-        };
-        if a.x != b.x {
-            Self::X {
-                x: (a.x)..=(b.x),
-                y: a.y,
-                z: a.z,
-            }
-        } else if a.y != b.y {
-            Self::Y {
-                x: a.x,
-                y: (a.y)..=(b.y),
-                z: a.z,
-            }
-        } else if a.z != b.z {
-            Self::Z {
-                x: a.x,
-                y: a.y,
-                z: (a.z)..=(b.z),
-            }
-        } else {
-            Self::Singleton(a)
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Triple {
-    pub x: usize,
-    pub y: usize,
-    pub z: usize,
-}
-
-impl Triple {
-    fn from_str(data: &str) -> Self {
-        let mut buf = [0; 3];
-
-        for (x, b) in data.split(',').zip(buf.iter_mut()) {
-            *b = x.parse().expect("Triples contain numbers");
-        }
-
-        Self {
-            x: buf[0],
-            y: buf[1],
-            z: buf[2],
-        }
-    }
+fn brick_from_ends(a: Vec3<usize>, b: Vec3<usize>) -> Brick {
+    let corner = |v: Vec3<usize>| [v.x as i64, v.y as i64, v.z as i64];
+    Brick::from_corners(corner(a), corner(b))
 }
 
 #[cfg(test)]
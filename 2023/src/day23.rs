@@ -1,11 +1,8 @@
-use {
-    aoc_core::{
-        basic_grid,
-        grid::{Grid, Position},
-        iter::AtMost,
-        Solution,
-    },
-    std::collections::HashSet,
+use aoc_core::{
+    basic_grid,
+    graph::corridors::CorridorGraph,
+    grid::{Grid, Position},
+    Solution,
 };
 
 pub struct Day23;
@@ -20,33 +17,22 @@ impl Solution<'_> for Day23 {
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let start = find_start(&input);
-        let end = find_end(&input);
-
         let nc = input.neighbor_context();
-        let neighbors = |x: Position| -> AtMost<Position, 4> {
+        let can_move = |x: Position, y: Position| -> bool {
             match input[x] {
-                b'>' => AtMost::some(nc.right(x)),
-                b'<' => AtMost::some(nc.left(x)),
-                b'^' => AtMost::some(nc.up(x)),
-                b'v' => AtMost::some(nc.down(x)),
-                _ => AtMost::some(nc.cardinal_neighbors_of(x).filter(|y| input[*y] != b'#')),
+                b'>' => nc.right(x) == Some(y),
+                b'<' => nc.left(x) == Some(y),
+                b'^' => nc.up(x) == Some(y),
+                b'v' => nc.down(x) == Some(y),
+                _ => true,
             }
         };
 
-        longest_path(start, end, neighbors)
+        longest_path(&input, can_move)
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let start = find_start(&input);
-        let end = find_end(&input);
-
-        let nc = input.neighbor_context();
-        let neighbors = |x: Position| -> AtMost<Position, 4> {
-            AtMost::some(nc.cardinal_neighbors_of(x).filter(|y| input[*y] != b'#'))
-        };
-
-        longest_path(start, end, neighbors)
+        longest_path(&input, |_, _| true)
     }
 }
 
@@ -67,26 +53,14 @@ fn find_end(grid: &Grid<u8>) -> Position {
     (n - 1, end_col)
 }
 
-fn longest_path<F>(start: Position, end: Position, neighbors: F) -> usize
+fn longest_path<F>(grid: &Grid<u8>, can_move: F) -> usize
 where
-    F: Fn(Position) -> AtMost<Position, 4>,
+    F: Fn(Position, Position) -> bool,
 {
-    let mut stack = vec![(start, 0, HashSet::new())];
-    let mut complete = Vec::new();
-    while let Some((x, s, mut visited)) = stack.pop() {
-        if visited.contains(&x) {
-            continue;
-        }
-        if x == end {
-            complete.push(s);
-            continue;
-        }
-        visited.insert(x);
-        for y in neighbors(x).into_iter().filter(|y| !visited.contains(y)) {
-            stack.push((y, s + 1, visited.clone()));
-        }
-    }
-    complete.into_iter().max().unwrap()
+    let start = find_start(grid);
+    let end = find_end(grid);
+    let graph = CorridorGraph::from_grid(grid, &[start, end], |b| *b != b'#', can_move);
+    graph.longest_path(0, 1).expect("End is reachable") as usize
 }
 
 #[cfg(test)]
@@ -1,9 +1,13 @@
-use aoc_core::{blocks, Solution};
+use aoc_core::{
+    blocks,
+    intervals::{IntervalSet, OffsetMap},
+    Solution,
+};
 
 pub struct Day5;
 
 impl Solution<'_> for Day5 {
-    type Input = (Vec<u64>, SeedToLocation);
+    type Input = (Vec<u64>, OffsetMap<u64>);
     type Output1 = u64;
     type Output2 = u64;
 
@@ -21,33 +25,12 @@ impl Solution<'_> for Day5 {
             .map(|x| x.parse().expect("Seeds are numbers"))
             .collect();
 
-        let seed_to_soil = Map::parse(iter.next().unwrap());
-        let soil_to_fertilizer = Map::parse(iter.next().unwrap());
-        let fertilizer_to_water = Map::parse(iter.next().unwrap());
-        let water_to_light = Map::parse(iter.next().unwrap());
-        let light_to_temperature = Map::parse(iter.next().unwrap());
-        let temperature_to_humidity = Map::parse(iter.next().unwrap());
-        let humidity_to_location = Map::parse(iter.next().unwrap());
-
-        let seed_to_location = ComposeMap {
-            map1: seed_to_soil,
-            map2: ComposeMap {
-                map1: soil_to_fertilizer,
-                map2: ComposeMap {
-                    map1: fertilizer_to_water,
-                    map2: ComposeMap {
-                        map1: water_to_light,
-                        map2: ComposeMap {
-                            map1: light_to_temperature,
-                            map2: ComposeMap {
-                                map1: temperature_to_humidity,
-                                map2: humidity_to_location,
-                            },
-                        },
-                    },
-                },
-            },
-        };
+        // The maps go seed-to-soil, soil-to-fertilizer, ..., humidity-to-location,
+        // so composing them in order gives seed-to-location.
+        let seed_to_location = iter
+            .map(parse_map)
+            .reduce(|f, g| f.then(&g))
+            .expect("At least one map");
         (seeds, seed_to_location)
     }
 
@@ -55,132 +38,40 @@ impl Solution<'_> for Day5 {
         let (seeds, seed_to_location) = input;
         seeds
             .into_iter()
-            .map(|x| seed_to_location.map(x))
+            .map(|x| seed_to_location.apply(x))
             .min()
             .unwrap()
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
         let (seeds, seed_to_location) = input;
-        // Cheating here by checking things in parallel. Probably there is a clever way
-        // to do this instead of brute force, but this works fine on my machine (runtime ~ 1min).
-        std::thread::scope(|s| {
-            let threads = seeds
-                .chunks_exact(2)
-                .map(|x| match x {
-                    [start, length] => s.spawn(|| {
-                        let start = *start;
-                        let end = start + *length;
-                        (start..end).map(|x| seed_to_location.map(x)).min().unwrap()
-                    }),
-                    _ => unreachable!(),
-                })
-                .collect::<Vec<_>>();
-            threads
-                .into_iter()
-                .map(|t| t.join().unwrap())
-                .min()
-                .unwrap()
-        })
+        let seeds: IntervalSet<u64> = seeds
+            .chunks_exact(2)
+            .map(|x| match x {
+                [start, length] => *start..(*start + *length),
+                _ => unreachable!(),
+            })
+            .collect();
+        // Map all the seed ranges at once instead of seed by seed.
+        seed_to_location.apply_set(&seeds).min().unwrap()
     }
 }
 
-pub trait CanMap {
-    fn map(&self, x: u64) -> u64;
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct MapRange {
-    dest_start: u64,
-    source_start: u64,
-    length: u64,
-}
-
-impl MapRange {
-    pub fn from_line(line: &str) -> Self {
+fn parse_map(block: &str) -> OffsetMap<u64> {
+    let mut map = OffsetMap::new();
+    for line in block.lines().skip(1) {
         let mut iter = line
             .trim()
             .splitn(3, ' ')
             .map(|x| x.parse().expect("Must be numbers"));
         let dest_start = iter.next().unwrap();
-        let source_start = iter.next().unwrap();
+        let source_start: u64 = iter.next().unwrap();
         let length = iter.next().unwrap();
-        Self {
-            dest_start,
-            source_start,
-            length,
-        }
-    }
-
-    pub fn contains(&self, x: &u64) -> bool {
-        (self.source_start..(self.source_start + self.length)).contains(x)
-    }
-
-    pub fn try_map(&self, x: u64) -> Option<u64> {
-        if self.contains(&x) {
-            let value = self.dest_start + (x - self.source_start);
-            Some(value)
-        } else {
-            None
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Map {
-    ranges: Vec<MapRange>,
-}
-
-impl Map {
-    pub fn parse(block: &str) -> Self {
-        let ranges = block.lines().skip(1).map(MapRange::from_line).collect();
-        Self { ranges }
-    }
-}
-
-impl CanMap for Map {
-    fn map(&self, x: u64) -> u64 {
-        self.ranges.iter().find_map(|r| r.try_map(x)).unwrap_or(x)
+        map.insert(source_start..(source_start + length), dest_start);
     }
+    map
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ComposeMap<T, U> {
-    map1: T,
-    map2: U,
-}
-
-impl<T, U> CanMap for ComposeMap<T, U>
-where
-    T: CanMap,
-    U: CanMap,
-{
-    fn map(&self, x: u64) -> u64 {
-        let y = self.map1.map(x);
-        self.map2.map(y)
-    }
-}
-
-pub type SeedToLocation = ComposeMap<
-    Map, // seed-to-soil
-    ComposeMap<
-        Map, // soil-to-fertilizer
-        ComposeMap<
-            Map, // fertilizer-to-water
-            ComposeMap<
-                Map, // water-to-light
-                ComposeMap<
-                    Map, // light-to-temperature
-                    ComposeMap<
-                        Map, // temperature-to-humidity
-                        Map, // humidity-to-location
-                    >,
-                >,
-            >,
-        >,
-    >,
->;
-
 #[cfg(test)]
 mod tests {
     use super::*;
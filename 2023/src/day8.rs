@@ -1,5 +1,9 @@
 use {
-    aoc_core::{lcm, Solution},
+    aoc_core::{
+        number_theory::first_alignment,
+        parse::{alphanumeric, delimited, lines, one_of, Parser},
+        Solution,
+    },
     std::collections::HashMap,
 };
 
@@ -11,19 +15,14 @@ impl<'a> Solution<'a> for Day8 {
     type Output2 = u64;
 
     fn parse_input(data: &'a str) -> Self::Input {
-        let (instructions, nodes) = data
-            .split_once("\n\n")
-            .expect("Break between instructions and nodes");
-        let instructions = instructions
-            .bytes()
-            .map(|b| match b {
-                b'L' => Instruction::Left,
-                b'R' => Instruction::Right,
-                _ => panic!("Unknown instruction"),
-            })
-            .collect();
-        let nodes = nodes.lines().map(Node::parse).collect();
-        (instructions, nodes)
+        let instruction = one_of(&[("L", Instruction::Left), ("R", Instruction::Right)]);
+        let (instructions, nodes) = instruction
+            .many()
+            .skip("\n\n")
+            .then(lines(Node::parser()))
+            .parse(data)
+            .expect("Instructions and nodes");
+        (instructions, nodes.into_iter().collect())
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
@@ -33,18 +32,105 @@ impl<'a> Solution<'a> for Day8 {
 
     fn part_2(input: Self::Input) -> Self::Output2 {
         let (instructions, nodes) = input;
-        let n_steps = nodes.keys().filter_map(|label| {
-            if !label.ends_with('A') {
-                return None;
-            }
-            Some(count_steps(
-                label,
-                |l| l.ends_with('Z'),
-                &instructions,
-                &nodes,
-            ))
-        });
-        n_steps.reduce(lcm).expect("At least one starting point")
+        let ghosts: Vec<EndTimes> = nodes
+            .keys()
+            .filter(|label| label.ends_with('A'))
+            .map(|label| end_times(label, |l| l.ends_with('Z'), &instructions, &nodes))
+            .collect();
+        assert!(!ghosts.is_empty(), "At least one starting point");
+        first_common_time(&ghosts).expect("Ghosts reach the end together")
+    }
+}
+
+// The steps at which a walk meets its end condition. The walk is periodic once
+// it returns to a `(node, instruction)` state it has been in before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EndTimes {
+    // Steps before the walk enters its cycle.
+    before_cycle: Vec<u64>,
+    // Steps within the first pass around the cycle, each repeating every `period`.
+    in_cycle: Vec<u64>,
+    period: u64,
+}
+
+impl EndTimes {
+    fn contains(&self, t: u64) -> bool {
+        self.before_cycle.contains(&t)
+            || self
+                .in_cycle
+                .iter()
+                .any(|offset| t >= *offset && (t - offset).is_multiple_of(self.period))
+    }
+}
+
+fn end_times<'a, F>(
+    start: &'a str,
+    end_condition: F,
+    instructions: &[Instruction],
+    nodes: &HashMap<&'a str, Node<'a>>,
+) -> EndTimes
+where
+    F: Fn(&'_ str) -> bool,
+{
+    let mut seen: HashMap<(&str, usize), u64> = HashMap::new();
+    let mut ends = Vec::new();
+    let mut current_node = start;
+    let mut count = 0;
+    let cycle_start = loop {
+        let i = count as usize % instructions.len();
+        if let Some(first) = seen.insert((current_node, i), count) {
+            break first;
+        }
+        if end_condition(current_node) {
+            ends.push(count);
+        }
+        current_node = step(current_node, &instructions[i], nodes);
+        count += 1;
+    };
+
+    let (before_cycle, in_cycle) = ends.into_iter().partition(|t| *t < cycle_start);
+    EndTimes {
+        before_cycle,
+        in_cycle,
+        period: count - cycle_start,
+    }
+}
+
+// The first step at which every walk meets its end condition.
+fn first_common_time(walks: &[EndTimes]) -> Option<u64> {
+    // Times before some walk has entered its cycle are checked directly.
+    let early = walks
+        .iter()
+        .flat_map(|w| w.before_cycle.iter().copied())
+        .filter(|t| walks.iter().all(|w| w.contains(*t)))
+        .min();
+
+    // Otherwise every walk is in its cycle, so try each choice of end offsets.
+    let mut choices: Vec<Vec<(u64, u64)>> = vec![Vec::new()];
+    for walk in walks {
+        choices = choices
+            .into_iter()
+            .flat_map(|chosen| {
+                walk.in_cycle.iter().map(move |offset| {
+                    let mut chosen = chosen.clone();
+                    chosen.push((*offset, walk.period));
+                    chosen
+                })
+            })
+            .collect();
+    }
+    let periodic = choices
+        .iter()
+        .filter_map(|cycles| first_alignment(cycles))
+        .min();
+
+    early.into_iter().chain(periodic).min()
+}
+
+fn step<'a>(node: &str, instruction: &Instruction, nodes: &HashMap<&'a str, Node<'a>>) -> &'a str {
+    match instruction {
+        Instruction::Left => nodes[node].left,
+        Instruction::Right => nodes[node].right,
     }
 }
 
@@ -62,10 +148,7 @@ where
     let mut count = 0;
     while !end_condition(current_node) {
         let instruction = iter.next().unwrap();
-        current_node = match instruction {
-            Instruction::Left => nodes[current_node].left,
-            Instruction::Right => nodes[current_node].right,
-        };
+        current_node = step(current_node, instruction, nodes);
         count += 1;
     }
     count
@@ -84,18 +167,13 @@ pub struct Node<'a> {
 }
 
 impl<'a> Node<'a> {
-    fn parse(data: &'a str) -> (&'a str, Node<'a>) {
-        let (label, remainder) = data.split_once('=').expect("Contains equals");
-        let (left, right) = remainder.trim().split_once(',').expect("Contains comma");
-        let left = left.trim();
-        let right = right.trim();
-        (
-            label.trim(),
-            Self {
-                left: &left[1..],
-                right: &right[0..(right.len() - 1)],
-            },
-        )
+    // e.g. `AAA = (BBB, CCC)`, labelled by the left hand side.
+    fn parser() -> impl Parser<'a, (&'a str, Self)> {
+        let children = alphanumeric().skip(", ").then(alphanumeric());
+        alphanumeric()
+            .skip(" = ")
+            .then(delimited("(", children, ")"))
+            .map(|(label, (left, right))| (label, Self { left, right }))
     }
 }
 
@@ -119,4 +197,29 @@ mod tests {
         let output = Day8::part_2(input);
         assert_eq!(output, 6);
     }
+
+    #[test]
+    fn test_end_reached_once() {
+        // The first ghost only passes its end once, before getting stuck.
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (11B, 11B)\n\
+                     22A = (22Z, 22Z)\n22Z = (22Z, 22Z)";
+        let (instructions, nodes) = Day8::parse_input(input);
+        let first = end_times("11A", |l| l.ends_with('Z'), &instructions, &nodes);
+        assert_eq!(
+            first,
+            EndTimes {
+                before_cycle: vec![1],
+                in_cycle: vec![],
+                period: 1
+            }
+        );
+        assert_eq!(Day8::part_2(Day8::parse_input(input)), 1);
+
+        let never = EndTimes {
+            before_cycle: vec![2],
+            in_cycle: vec![],
+            period: 1,
+        };
+        assert_eq!(first_common_time(&[first, never]), None);
+    }
 }
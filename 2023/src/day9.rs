@@ -1,4 +1,4 @@
-use aoc_core::{linked_list::LinkedList, Solution};
+use aoc_core::{poly::Differences, Solution};
 
 pub struct Day9;
 
@@ -27,47 +27,12 @@ impl Solution<'_> for Day9 {
 }
 
 fn extrapolate_forward(xs: Vec<i64>) -> i64 {
-    let matrix = create_matrix(xs);
-    let (mut zeros, mut matrix) = matrix.pop().unwrap();
-    zeros.push(0);
-    let mut prev_row = zeros;
-    while let LinkedList::Cons { mut head, tail } = matrix {
-        let next_elem = prev_row.last().copied().unwrap() + head.last().copied().unwrap();
-        head.push(next_elem);
-        prev_row = head;
-        matrix = *tail;
-    }
-    prev_row.last().copied().unwrap()
+    let n = xs.len() as i64;
+    Differences::from_samples(&xs).extrapolate(n)
 }
 
 fn extrapolate_backward(xs: Vec<i64>) -> i64 {
-    let matrix = create_matrix(xs);
-    let (mut zeros, mut matrix) = matrix.pop().unwrap();
-    zeros.insert(0, 0);
-    let mut prev_row = zeros;
-    while let LinkedList::Cons { mut head, tail } = matrix {
-        let first_elem = head.first().copied().unwrap() - prev_row.first().copied().unwrap();
-        head.insert(0, first_elem);
-        prev_row = head;
-        matrix = *tail;
-    }
-    prev_row.first().copied().unwrap()
-}
-
-fn create_matrix(xs: Vec<i64>) -> LinkedList<Vec<i64>> {
-    let mut matrix = LinkedList::new().cons(xs);
-    while matrix.head().unwrap().iter().any(|&x| x != 0) {
-        let ys = first_diff(matrix.head().unwrap());
-        matrix = matrix.cons(ys);
-    }
-    matrix
-}
-
-fn first_diff(xs: &[i64]) -> Vec<i64> {
-    xs.iter()
-        .zip(xs.iter().skip(1))
-        .map(|(&a, &b)| b - a)
-        .collect()
+    Differences::from_samples(&xs).extrapolate(-1)
 }
 
 #[cfg(test)]
@@ -1,7 +1,4 @@
-use {
-    aoc_core::{n_digits, Solution},
-    std::collections::HashMap,
-};
+use aoc_core::{memo::Memo, n_digits, Solution};
 
 pub struct Day11;
 
@@ -15,44 +12,39 @@ impl Solution<'_> for Day11 {
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let mut memoize = HashMap::new();
+        let mut memo = Memo::new();
         input
             .into_iter()
-            .map(|number| stones_length(number, 25, &mut memoize))
+            .map(|number| memo.call((number, 25), stones_length))
             .sum()
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let mut memoize = HashMap::new();
+        let mut memo = Memo::new();
         input
             .into_iter()
-            .map(|number| stones_length(number, 75, &mut memoize))
+            .map(|number| memo.call((number, 75), stones_length))
             .sum()
     }
 }
 
-fn stones_length(number: u64, steps: u8, memoize: &mut HashMap<(u64, u8), u64>) -> u64 {
+fn stones_length(memo: &mut Memo<(u64, u8), u64>, (number, steps): (u64, u8)) -> u64 {
     if steps == 0 {
         return 1;
     }
 
-    if let Some(answer) = memoize.get(&(number, steps)) {
-        return *answer;
-    }
-
+    let mut next = |number| memo.call((number, steps - 1), stones_length);
     let n_digits = n_digits(number);
-    let answer = if number == 0 {
-        stones_length(1, steps - 1, memoize)
+    if number == 0 {
+        next(1)
     } else if n_digits.is_multiple_of(2) {
         let p10 = 10_u64.pow(n_digits / 2);
         let left = number / p10;
         let right = number % p10;
-        stones_length(left, steps - 1, memoize) + stones_length(right, steps - 1, memoize)
+        next(left) + next(right)
     } else {
-        stones_length(number * 2024, steps - 1, memoize)
-    };
-    memoize.insert((number, steps), answer);
-    answer
+        next(number * 2024)
+    }
 }
 
 #[cfg(test)]
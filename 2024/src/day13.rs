@@ -1,4 +1,10 @@
-use aoc_core::{blocks, digit_value, iter::AtMost, Solution};
+use aoc_core::{
+    blocks,
+    matrix::{LinearSolution, Matrix},
+    rational::Rational,
+    scan::integer_array,
+    Solution,
+};
 
 pub struct Day13;
 
@@ -10,13 +16,11 @@ impl Solution<'_> for Day13 {
     fn parse_input(data: &str) -> Self::Input {
         blocks(data)
             .map(|block| {
-                let numbers = AtMost::<[i64; 2], 3>::some(block.lines().map(i64_pair));
-                let [Some([a, b]), Some([c, d]), Some(goal)] = numbers.inner else {
-                    panic!("3 lines per block")
-                };
+                let [a, b, c, d, x, y] =
+                    integer_array(block).expect("Two buttons and a prize per block");
                 Input {
                     matrix: [[a, c], [b, d]],
-                    goal,
+                    goal: [x, y],
                 }
             })
             .collect()
@@ -46,43 +50,20 @@ where
 }
 
 fn solve(matrix: [[i64; 2]; 2], goal: [i64; 2]) -> Option<[i64; 2]> {
-    let [[a, b], [c, d]] = matrix;
-    let det = (a * d) - (b * c);
-
-    // Row swap to change determinant sign
-    if det < 0 {
-        return solve([[c, d], [a, b]], [goal[1], goal[0]]);
-    }
-    debug_assert!(det > 0);
-
-    let [x, y] = goal;
-
-    let n = (d * x) - (b * y);
-    let m = (a * y) - (c * x);
-
-    if n % det != 0 || m % det != 0 {
+    let matrix = Matrix::from_integers(
+        matrix
+            .iter()
+            .map(|row| row.iter().map(|x| *x as i128).collect())
+            .collect(),
+    );
+    let goal = goal.map(|x| Rational::integer(x as i128));
+
+    let LinearSolution::Unique(solution) = matrix.solve(&goal) else {
         return None;
-    }
-
-    Some([n / det, m / det])
-}
-
-fn i64_pair(line: &str) -> [i64; 2] {
-    let (left, right) = line.split_once(',').expect("Has comma");
-    [parse_i64(left), parse_i64(right)]
-}
-
-fn parse_i64(line: &str) -> i64 {
-    let mut result = 0;
-    let mut p10 = 1;
-    for c in line.bytes().rev() {
-        let Some(d) = digit_value(c) else {
-            break;
-        };
-        result += (d as i64) * p10;
-        p10 *= 10;
-    }
-    result
+    };
+    let n = solution[0].to_integer()?;
+    let m = solution[1].to_integer()?;
+    Some([n as i64, m as i64])
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
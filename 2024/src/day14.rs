@@ -1,4 +1,4 @@
-use aoc_core::{grid::Grid, strip_label, Solution};
+use aoc_core::{grid::Grid, scan, vector::Vec2, Solution};
 
 pub struct Day14;
 
@@ -10,10 +10,11 @@ impl Solution<'_> for Day14 {
     fn parse_input(data: &str) -> Self::Input {
         data.lines()
             .map(|line| {
-                let (position, velocity) = line.split_once(' ').expect("Space separated");
-                let position = Pair::from_str(strip_label(position, '='));
-                let velocity = Pair::from_str(strip_label(velocity, '='));
-                Robot { position, velocity }
+                let (px, py, vx, vy) = scan!("p={},{} v={},{}", line).expect("Robot description");
+                Robot {
+                    position: Pair::new(px, py),
+                    velocity: Pair::new(vx, vy),
+                }
             })
             .collect()
     }
@@ -75,12 +76,8 @@ fn part_1_solution(robots: &mut [Robot], max_x: i64, max_y: i64) -> i64 {
 
 fn simulate_step(robots: &mut [Robot], max_x: i64, max_y: i64) {
     for r in robots {
-        let Pair { x, y } = r.position;
-        let Pair { x: vx, y: vy } = r.velocity;
-        r.position = Pair {
-            x: (x + vx + max_x) % max_x,
-            y: (y + vy + max_y) % max_y,
-        }
+        let Pair { x, y } = r.position + r.velocity;
+        r.position = Pair::new(x.rem_euclid(max_x), y.rem_euclid(max_y));
     }
 }
 
@@ -111,21 +108,7 @@ fn safety_score(robots: &[Robot], max_x: i64, max_y: i64) -> i64 {
     total[0] * total[1] * total[2] * total[3]
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Pair {
-    x: i64,
-    y: i64,
-}
-
-impl Pair {
-    fn from_str(line: &str) -> Self {
-        let (x, y) = line.split_once(',').expect("Comma separated");
-        Self {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        }
-    }
-}
+type Pair = Vec2<i64>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Robot {
@@ -1,8 +1,9 @@
 use {
     aoc_core::{
         basic_grid,
-        grid::{dijkstra_shortest_path, Grid, NeighborsCreator, Position},
-        iter::AtMost,
+        graph::corridors::CorridorGraph,
+        grid::{difference, dijkstra_shortest_path, Grid, Position},
+        small_vec::SmallVec,
         Solution,
     },
     std::collections::{HashMap, HashSet},
@@ -23,98 +24,135 @@ impl Solution<'_> for Day16 {
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let (dist, _) = solve_maze(&input);
-        let end_position = find_end_position(&input);
-        dist.into_iter()
-            .filter_map(|(node, score)| {
-                if node.position == end_position {
-                    Some(score)
-                } else {
-                    None
-                }
-            })
-            .min()
-            .expect("End is reachable")
+        let maze = Maze::new(&input);
+        let (dist, _) = maze.solve();
+        maze.best_ends(&dist).1
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let (dist, prev) = solve_maze(&input);
-        let end_position = find_end_position(&input);
-        let (end_node, _) = dist
-            .into_iter()
-            .filter(|(n, _)| n.position == end_position)
-            .min_by_key(|(_, s)| *s)
-            .expect("End is reachable");
-        count_path(&end_node, &prev)
+        let maze = Maze::new(&input);
+        let (dist, prev) = maze.solve();
+        let (ends, _) = maze.best_ends(&dist);
+        maze.count_path(ends, &prev)
     }
 }
 
-fn find_end_position(input: &Grid<u8>) -> Position {
-    input
-        .index_range()
-        .find(|x| input[*x] == b'E')
-        .expect("Has end")
+// The maze compressed to its junctions, where the reindeer can turn. Turns
+// inside corridors are included in the cost of the corridor.
+struct Maze {
+    graph: CorridorGraph,
 }
 
-fn count_path(end: &Node, prev: &HashMap<Node, Vec<Node>>) -> usize {
-    let mut visited = HashSet::new();
-    let mut path = HashSet::new();
-    let mut stack = vec![end];
-    while let Some(node) = stack.pop() {
-        if visited.contains(node) {
-            continue;
-        }
-        visited.insert(node);
-        path.insert(node.position);
-        for p in prev.get(node).into_iter().flatten() {
-            stack.push(p);
-        }
+// The start is junction 0 and the end is junction 1.
+const START: usize = 0;
+const END: usize = 1;
+
+impl Maze {
+    fn new(input: &Grid<u8>) -> Self {
+        let find = |b| input.index_of(&b).expect("Has start and end");
+        let step_cost = |prev: Option<Position>, current, next| match prev {
+            Some(prev) if difference(prev, current) != difference(current, next) => {
+                ROTATE_COST + MOVEMENT_COST
+            }
+            _ => MOVEMENT_COST,
+        };
+        let graph = CorridorGraph::from_grid_weighted(
+            input,
+            &[find(b'S'), find(b'E')],
+            |b| *b != b'#',
+            |_, _| true,
+            step_cost,
+        );
+        Self { graph }
     }
-    path.len()
-}
 
-fn solve_maze(input: &Grid<u8>) -> (HashMap<Node, u64>, HashMap<Node, Vec<Node>>) {
-    let nc = input.neighbor_context();
-    let start_position = input
-        .index_range()
-        .find(|x| input[*x] == b'S')
-        .expect("Has start");
-    let start_node = Node {
-        position: start_position,
-        direction: Direction::East,
-    };
-
-    let weights = |u: &Node, v: &Node| -> u64 {
-        if u.direction != v.direction {
-            ROTATE_COST
-        } else {
-            MOVEMENT_COST
+    // The corridor leaving the node's junction in the direction it is facing.
+    fn corridor(&self, node: &Node) -> Option<usize> {
+        let position = self.graph.junctions[node.junction];
+        self.graph.corridors[node.junction]
+            .iter()
+            .position(|c| Direction::between(position, c.first) == node.direction)
+    }
+
+    fn solve(&self) -> (HashMap<Node, u64>, HashMap<Node, Vec<Node>>) {
+        let start_node = Node {
+            junction: START,
+            direction: Direction::East,
+        };
+
+        let weights = |u: &Node, v: &Node| -> u64 {
+            if u.junction == v.junction {
+                ROTATE_COST
+            } else {
+                let c = self.corridor(u).expect("Moves along a corridor");
+                self.graph.corridors[u.junction][c].cost
+            }
+        };
+
+        let neighbors = |x: &Node| -> SmallVec<Node, 3> {
+            let mut result: SmallVec<Node, 3> = x
+                .direction
+                .rotate()
+                .into_iter()
+                .map(|direction| Node {
+                    junction: x.junction,
+                    direction,
+                })
+                .collect();
+            result.extend(self.corridor(x).map(|c| {
+                let corridor = &self.graph.corridors[x.junction][c];
+                Node {
+                    junction: corridor.to,
+                    direction: Direction::between(corridor.last, self.graph.junctions[corridor.to]),
+                }
+            }));
+            result
+        };
+
+        dijkstra_shortest_path(start_node, weights, neighbors)
+    }
+
+    // All nodes at the end with the lowest score, and that score.
+    fn best_ends(&self, dist: &HashMap<Node, u64>) -> (Vec<Node>, u64) {
+        let best = dist
+            .iter()
+            .filter(|(n, _)| n.junction == END)
+            .map(|(_, s)| *s)
+            .min()
+            .expect("End is reachable");
+        let ends = dist
+            .iter()
+            .filter(|(n, s)| n.junction == END && **s == best)
+            .map(|(n, _)| *n)
+            .collect();
+        (ends, best)
+    }
+
+    // Number of cells on any best path to one of the `ends`.
+    fn count_path(&self, ends: Vec<Node>, prev: &HashMap<Node, Vec<Node>>) -> usize {
+        let mut visited = HashSet::new();
+        let mut path = HashSet::new();
+        let mut stack = ends;
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            path.insert(self.graph.junctions[node.junction]);
+            for p in prev.get(&node).into_iter().flatten() {
+                if p.junction != node.junction {
+                    let c = self.corridor(p).expect("Moved along a corridor");
+                    path.extend(&self.graph.corridors[p.junction][c].cells);
+                }
+                stack.push(*p);
+            }
         }
-    };
-
-    let neighbors = |x: &Node| -> AtMost<Node, 3> {
-        let position = x.position;
-        let direction = x.direction;
-        let mut result = AtMost::some(direction.rotate().into_iter().map(|direction| Node {
-            position,
-            direction,
-        }));
-        result.inner[2] = direction
-            .apply(position, &nc)
-            .filter(|x| input[*x] != b'#')
-            .map(|position| Node {
-                position,
-                direction,
-            });
-        result
-    };
-
-    dijkstra_shortest_path(start_node, weights, neighbors)
+        path.len()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct Node {
-    position: Position,
+    junction: usize,
     direction: Direction,
 }
 
@@ -127,12 +165,14 @@ enum Direction {
 }
 
 impl Direction {
-    fn apply(self, x: Position, nc: &NeighborsCreator) -> Option<Position> {
-        match self {
-            Self::North => nc.up(x),
-            Self::East => nc.right(x),
-            Self::South => nc.down(x),
-            Self::West => nc.left(x),
+    // Direction of a step between adjacent cells.
+    fn between(x: Position, y: Position) -> Self {
+        match difference(x, y) {
+            (-1, 0) => Self::North,
+            (0, 1) => Self::East,
+            (1, 0) => Self::South,
+            (0, -1) => Self::West,
+            d => panic!("Cells are not adjacent: {d:?}"),
         }
     }
 
@@ -1,4 +1,7 @@
-use aoc_core::{iter::AtMost, strip_label, Solution};
+use aoc_core::{
+    scan::{integer_array, integers},
+    Solution,
+};
 
 pub struct Day17;
 
@@ -9,16 +12,8 @@ impl Solution<'_> for Day17 {
 
     fn parse_input(data: &str) -> Self::Input {
         let (state, program) = data.split_once("\n\n").expect("Blank line");
-        let state = AtMost::<usize, 3>::some(
-            state
-                .lines()
-                .map(|l| strip_label(l, ':').parse().expect("Registers are numbers")),
-        );
-        let program = strip_label(program, ':')
-            .split(',')
-            .map(|x| x.parse().expect("Opcodes are numbers"))
-            .collect();
-        (State::new(state), program)
+        let [a, b, c] = integer_array(state).expect("Three registers");
+        (State { a, b, c }, integers(program).collect())
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
@@ -47,16 +42,6 @@ pub struct State {
     c: usize,
 }
 
-impl State {
-    fn new(registers: AtMost<usize, 3>) -> Self {
-        Self {
-            a: registers.inner[0].unwrap(),
-            b: registers.inner[1].unwrap(),
-            c: registers.inner[2].unwrap(),
-        }
-    }
-}
-
 fn combo_operand(operand: u8, state: &State) -> usize {
     match operand {
         x if x <= 3 => operand as usize,
@@ -1,7 +1,7 @@
 use {
     aoc_core::{
         binary_search,
-        grid::{dijkstra_shortest_path, NeighborsCreator, Position},
+        grid::{dial_shortest_path, NeighborsCreator, Position},
         iter::AtMost,
         Solution,
     },
@@ -48,7 +48,7 @@ fn shortest_path(walls: &HashSet<Position>, max_position: Position) -> Option<u6
         AtMost::some(nc.cardinal_neighbors_of(*u).filter(|v| !walls.contains(v)))
     };
 
-    let (dist, _) = dijkstra_shortest_path((0, 0), weights, neighbors);
+    let dist = dial_shortest_path((0, 0), weights, neighbors);
     dist.get(&max_position).copied()
 }
 
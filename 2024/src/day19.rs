@@ -1,6 +1,9 @@
 use {
-    aoc_core::Solution,
-    std::collections::{HashMap, HashSet},
+    aoc_core::{
+        memo::{ArrayCache, Cache, Memo},
+        Solution,
+    },
+    std::collections::HashSet,
 };
 
 pub struct Day19;
@@ -28,11 +31,13 @@ impl<'a> Solution<'a> for Day19 {
 
     fn part_2(input: Self::Input) -> Self::Output2 {
         let (towels, patterns) = input;
-        let mut memoize = HashMap::new();
-        memoize.insert("", 1);
         patterns
             .into_iter()
-            .map(|p| number_patterns(p, &towels, &mut memoize))
+            .map(|p| {
+                // Everything left to match is a suffix of `p`, so its length identifies it.
+                let mut memo = Memo::with_cache(ArrayCache::new(p.len() + 1, |s: &&str| s.len()));
+                memo.call(p, |memo, p| number_patterns(memo, p, &towels))
+            })
             .sum()
     }
 }
@@ -46,23 +51,20 @@ fn is_possible_pattern<'a>(pattern: &'a str, towels: &HashSet<&'a str>) -> bool
     next_patterns.any(|p| is_possible_pattern(p, towels))
 }
 
-fn number_patterns<'a>(
+fn number_patterns<'a, C: Cache<&'a str, u64>>(
+    memo: &mut Memo<&'a str, u64, C>,
     pattern: &'a str,
     towels: &HashSet<&'a str>,
-    memoize: &mut HashMap<&'a str, u64>,
 ) -> u64 {
-    if let Some(answer) = memoize.get(pattern) {
-        return *answer;
+    if pattern.is_empty() {
+        return 1;
     }
 
-    let mut total = 0;
-    let next_patterns = towels.iter().filter_map(|t| pattern.strip_prefix(t));
-    for p in next_patterns {
-        let n = number_patterns(p, towels, memoize);
-        memoize.insert(p, n);
-        total += n;
-    }
-    total
+    towels
+        .iter()
+        .filter_map(|t| pattern.strip_prefix(t))
+        .map(|p| memo.call(p, |memo, p| number_patterns(memo, p, towels)))
+        .sum()
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 use {
     aoc_core::{
         abs_diff, basic_grid,
-        grid::{dijkstra_shortest_path, Grid, Position},
+        grid::{dial_shortest_path, Grid, Position},
         Solution,
     },
     std::collections::HashMap,
@@ -45,7 +45,7 @@ fn find_distances_to_end(grid: &Grid<u8>) -> HashMap<Position, u64> {
     }
     let neighbors = |x: &Position| nc.cardinal_neighbors_of(*x).filter(|y| grid[*y] != b'#');
 
-    dijkstra_shortest_path(start, weights, neighbors).0
+    dial_shortest_path(start, weights, neighbors)
 }
 
 fn find_2_ps_cheats(
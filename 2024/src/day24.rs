@@ -1,42 +1,72 @@
 use {
-    aoc_core::Solution,
-    std::collections::{HashMap, VecDeque},
+    aoc_core::{
+        intern::{Id, IdVec, Interner},
+        parse::{alphanumeric, lines, one_of, unsigned, Parser},
+        Solution,
+    },
+    std::collections::VecDeque,
 };
 
 pub struct Day24;
 
 impl<'a> Solution<'a> for Day24 {
-    type Input = (HashMap<&'a str, u8>, VecDeque<Gate<'a>>);
+    type Input = Circuit<'a>;
     type Output1 = u64;
     type Output2 = String;
 
     fn parse_input(data: &'a str) -> Self::Input {
-        let (inputs_block, gates_block) = data.split_once("\n\n").expect("Blank line");
-
-        let mut inputs = HashMap::new();
-        for line in inputs_block.lines() {
-            let (label, value) = line.split_once(':').expect("Has colon");
-            inputs.insert(label, value.trim().parse().unwrap());
+        // e.g. `x00: 1`
+        let input = alphanumeric().skip(": ").then(unsigned());
+        // e.g. `x00 AND y00 -> z00`
+        let gate = alphanumeric()
+            .skip(" ")
+            .then(one_of(Op::NAMES))
+            .skip(" ")
+            .then(alphanumeric())
+            .skip(" -> ")
+            .then(alphanumeric());
+
+        let (inputs, gates): (Vec<(&str, u8)>, _) = lines(input)
+            .skip("\n\n")
+            .then(lines(gate))
+            .parse(data)
+            .expect("Wire values and gates");
+
+        // Intern every wire before sizing the wire values, including inputs
+        // which no gate reads.
+        let mut labels = Interner::new();
+        let inputs: Vec<(Id, u8)> = inputs
+            .into_iter()
+            .map(|(label, value)| (labels.intern(label), value))
+            .collect();
+        let gates: VecDeque<Gate> = gates
+            .into_iter()
+            .map(|(((in1, op), in2), out)| Gate {
+                in1: labels.intern(in1),
+                in2: labels.intern(in2),
+                op,
+                out: labels.intern(out),
+            })
+            .collect();
+        let mut wires = IdVec::filled(labels.len(), None);
+        for (id, value) in inputs {
+            wires[id] = Some(value);
         }
-
-        let mut gates = VecDeque::new();
-        for line in gates_block.lines() {
-            let words: Vec<&'a str> = line.split(' ').collect();
-            gates.push_back(Gate {
-                in1: words[0],
-                in2: words[2],
-                op: Op::parse(words[1]),
-                out: words[4],
-            });
+        Circuit {
+            labels,
+            wires,
+            gates,
         }
-
-        (inputs, gates)
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let (mut wires, mut gates) = input;
+        let Circuit {
+            labels,
+            mut wires,
+            mut gates,
+        } = input;
         simulate(&mut wires, &mut gates);
-        get_number_from_bits("z", &wires)
+        get_number_from_bits("z", &labels, &wires)
     }
 
     // Need to match up the wrong_outputs with wrong_inner
@@ -45,14 +75,20 @@ impl<'a> Solution<'a> for Day24 {
         // This has a specific structure. We can look for gates
         // that fail to meet this structure.
 
-        let (mut wires, mut gates) = input;
+        let Circuit {
+            labels,
+            mut wires,
+            mut gates,
+        } = input;
+        let label = |id: Id| labels[id];
 
         // All output gates (except the last one) should be XOR
         let wrong_outputs: Vec<usize> = gates
             .iter()
             .enumerate()
             .filter_map(|(index, gate)| {
-                if gate.out.starts_with('z') && gate.out != "z45" && gate.op != Op::XOr {
+                let out = label(gate.out);
+                if out.starts_with('z') && out != "z45" && gate.op != Op::XOr {
                     Some(index)
                 } else {
                     None
@@ -65,7 +101,7 @@ impl<'a> Solution<'a> for Day24 {
             .iter()
             .enumerate()
             .filter_map(|(index, gate)| {
-                if is_inner_gate(gate) && gate.op == Op::XOr {
+                if is_inner_gate(gate, &labels) && gate.op == Op::XOr {
                     Some(index)
                 } else {
                     None
@@ -75,10 +111,10 @@ impl<'a> Solution<'a> for Day24 {
 
         // Need to match up the wrong_outputs with wrong_inner
         for &inner in &wrong_inner {
-            let goal = first_z_using(gates[inner].out, &gates).unwrap();
+            let goal = first_z_using(gates[inner].out, &gates, &labels).unwrap();
             let output = wrong_outputs
                 .iter()
-                .find(|index| gates[**index].out == goal)
+                .find(|index| label(gates[**index].out) == goal)
                 .copied()
                 .unwrap();
             let tmp = gates[inner].out;
@@ -88,33 +124,41 @@ impl<'a> Solution<'a> for Day24 {
 
         // With those swaps done, now we need to find the carry gates that are wrong.
         // We compare the right answer to the simulation and find where the carry was incorrect.
-        let x = get_number_from_bits("x", &wires);
-        let y = get_number_from_bits("y", &wires);
+        let x = get_number_from_bits("x", &labels, &wires);
+        let y = get_number_from_bits("y", &labels, &wires);
         simulate(&mut wires, &mut gates.clone());
-        let run = get_number_from_bits("z", &wires);
+        let run = get_number_from_bits("z", &labels, &wires);
         let false_carry = (x + y) ^ run;
         let trailing_zeros = format!("{:02}", false_carry.trailing_zeros());
         let trailing_gates = gates.iter().filter(|gate| {
-            gate.in1.ends_with(&trailing_zeros) && gate.in2.ends_with(&trailing_zeros)
+            label(gate.in1).ends_with(&trailing_zeros) && label(gate.in2).ends_with(&trailing_zeros)
         });
 
         // Finally, print all the wires we changed!
         let mut all_changed_gates: Vec<&'a str> = trailing_gates
             .chain(wrong_outputs.into_iter().map(|index| &gates[index]))
             .chain(wrong_inner.into_iter().map(|index| &gates[index]))
-            .map(|gate| gate.out)
+            .map(|gate| label(gate.out))
             .collect();
         all_changed_gates.sort();
         all_changed_gates.join(",")
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct Circuit<'a> {
+    labels: Interner<&'a str>,
+    // Value of each wire, once known.
+    wires: IdVec<Option<u8>>,
+    gates: VecDeque<Gate>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Gate<'a> {
-    in1: &'a str,
-    in2: &'a str,
+pub struct Gate {
+    in1: Id,
+    in2: Id,
     op: Op,
-    out: &'a str,
+    out: Id,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -125,71 +169,58 @@ pub enum Op {
 }
 
 impl Op {
-    pub fn parse(word: &str) -> Self {
-        match word {
-            "AND" => Self::And,
-            "XOR" => Self::XOr,
-            "OR" => Self::Or,
-            _ => panic!("Unexpected op"),
-        }
-    }
+    const NAMES: &'static [(&'static str, Self)] =
+        &[("AND", Self::And), ("XOR", Self::XOr), ("OR", Self::Or)];
 }
 
-fn is_ready<'a>(wires: &HashMap<&'a str, u8>, gate: &Gate<'a>) -> bool {
-    wires.contains_key(gate.in1) && wires.contains_key(gate.in2)
-}
-
-fn get_number_from_bits(prefix: &str, wires: &HashMap<&str, u8>) -> u64 {
+fn get_number_from_bits(prefix: &str, labels: &Interner<&str>, wires: &IdVec<Option<u8>>) -> u64 {
     let mut output = 0;
     let mut power = 1;
     for n in 0..46 {
         let wire = [prefix, &format!("{n:02}")].concat();
-        let Some(bit) = wires.get(wire.as_str()) else {
+        let Some(bit) = labels.get(wire.as_str()).and_then(|id| wires[id]) else {
             break;
         };
-        output += (*bit as u64) * power;
+        output += (bit as u64) * power;
         power *= 2;
     }
     output
 }
 
-fn simulate<'a>(wires: &mut HashMap<&'a str, u8>, gates: &mut VecDeque<Gate<'a>>) {
+fn simulate(wires: &mut IdVec<Option<u8>>, gates: &mut VecDeque<Gate>) {
     while let Some(gate) = gates.pop_front() {
-        if !is_ready(wires, &gate) {
+        let (Some(input1), Some(input2)) = (wires[gate.in1], wires[gate.in2]) else {
             gates.push_back(gate);
             continue;
-        }
-
-        let input1 = wires[gate.in1];
-        let input2 = wires[gate.in2];
+        };
         let output = match gate.op {
             Op::And => input1 & input2,
             Op::XOr => input1 ^ input2,
             Op::Or => input1 | input2,
         };
-        wires.insert(gate.out, output);
+        wires[gate.out] = Some(output);
     }
 }
 
-fn is_inner_gate(gate: &Gate<'_>) -> bool {
-    fn starts_with_x_or_y(s: &str) -> bool {
-        s.starts_with('x') || s.starts_with('y')
-    }
-    !starts_with_x_or_y(gate.in1) && !starts_with_x_or_y(gate.in2) && !gate.out.starts_with('z')
+fn is_inner_gate(gate: &Gate, labels: &Interner<&str>) -> bool {
+    let starts_with_x_or_y = |id: Id| labels[id].starts_with('x') || labels[id].starts_with('y');
+    !starts_with_x_or_y(gate.in1)
+        && !starts_with_x_or_y(gate.in2)
+        && !labels[gate.out].starts_with('z')
 }
 
-fn first_z_using<'a>(wire: &'a str, gates: &VecDeque<Gate<'a>>) -> Option<String> {
-    let users: Vec<&Gate<'a>> = gates
+fn first_z_using(wire: Id, gates: &VecDeque<Gate>, labels: &Interner<&str>) -> Option<String> {
+    let users: Vec<&Gate> = gates
         .iter()
         .filter(|g| g.in1 == wire || g.in2 == wire)
         .collect();
-    let maybe_z_user = users.iter().find(|g| g.out.starts_with('z'));
+    let maybe_z_user = users.iter().find(|g| labels[g.out].starts_with('z'));
     if let Some(z_user) = maybe_z_user {
-        let number = z_user.out[1..].parse::<usize>().unwrap() - 1;
+        let number = labels[z_user.out][1..].parse::<usize>().unwrap() - 1;
         Some(format!("z{number:02}"))
     } else {
         for gate in users {
-            let result = first_z_using(gate.out, gates);
+            let result = first_z_using(gate.out, gates, labels);
             if result.is_some() {
                 return result;
             }
@@ -210,4 +241,12 @@ mod tests {
         let output = Day24::part_1(input);
         assert_eq!(output, 2024);
     }
+
+    #[test]
+    fn test_unused_input_wire() {
+        // `x01` is not read by any gate.
+        let input = "x00: 1\nx01: 0\ny00: 1\ny01: 1\n\nx00 AND y00 -> z00\ny01 OR y00 -> z01";
+        let output = Day24::part_1(Day24::parse_input(input));
+        assert_eq!(output, 3);
+    }
 }
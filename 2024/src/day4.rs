@@ -1,8 +1,11 @@
-use aoc_core::{
-    basic_grid,
-    grid::{Grid, Position},
-    iter::AtMost,
-    Solution,
+use {
+    aoc_core::{
+        basic_grid,
+        grid::{Grid, Position},
+        small_vec::SmallVec,
+        Solution,
+    },
+    std::iter,
 };
 
 pub struct Day4;
@@ -33,7 +36,7 @@ impl Solution<'_> for Day4 {
         for x in x_positions {
             for dir in &directions {
                 let letters = repeat_3(x, dir);
-                if (&letters).into_iter().count() < 3 {
+                if letters.len() < 3 {
                     continue;
                 }
                 let is_xmas = letters
@@ -70,14 +73,11 @@ impl Solution<'_> for Day4 {
     }
 }
 
-fn repeat_3<F>(w: Position, f: F) -> AtMost<Position, 3>
+fn repeat_3<F>(w: Position, f: F) -> SmallVec<Position, 3>
 where
     F: Fn(Position) -> Option<Position>,
 {
-    let x = f(w);
-    let y = x.and_then(&f);
-    let z = y.and_then(&f);
-    AtMost { inner: [x, y, z] }
+    iter::successors(f(w), |x| f(*x)).take(3).collect()
 }
 
 #[cfg(test)]
@@ -1,11 +1,10 @@
-use {aoc_core::Solution, std::collections::HashMap};
+use aoc_core::{graph::DirectedGraph, iter::pairs, Solution};
 
 pub struct Day5;
 
-pub type Rule = (usize, fn(usize, usize) -> bool);
-
 impl Solution<'_> for Day5 {
-    type Input = (HashMap<usize, Vec<Rule>>, Vec<Vec<usize>>);
+    // Each rule `X|Y` is an edge from page X to page Y.
+    type Input = (DirectedGraph<usize>, Vec<Vec<usize>>);
     type Output1 = usize;
     type Output2 = usize;
 
@@ -14,15 +13,12 @@ impl Solution<'_> for Day5 {
             .split_once("\n\n")
             .expect("Blank line between sections");
 
-        let mut parsed_rules: HashMap<usize, Vec<Rule>> = HashMap::new();
+        let mut graph = DirectedGraph::new();
         for line in rules.lines() {
             let (earlier, later) = line.split_once('|').expect("Has pipe");
             let earlier: usize = earlier.parse().expect("Is number");
             let later: usize = later.parse().expect("Is number");
-            let rule1: Rule = (later, less);
-            let rule2: Rule = (earlier, greater);
-            parsed_rules.entry(earlier).or_default().push(rule1);
-            parsed_rules.entry(later).or_default().push(rule2);
+            graph.add_edge(earlier, later);
         }
 
         let pages = pages
@@ -30,83 +26,56 @@ impl Solution<'_> for Day5 {
             .map(|line| line.split(',').map(|x| x.parse().unwrap()).collect())
             .collect();
 
-        (parsed_rules, pages)
+        (graph, pages)
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
         let (rules, pages) = input;
-        let mut total = 0;
-        for ps in pages {
-            if check_rules(&ps, &rules) {
-                total += ps[ps.len() / 2];
-            }
-        }
-        total
+        pages
+            .iter()
+            .filter(|ps| is_ordered(ps, &rules))
+            .map(|ps| ps[ps.len() / 2])
+            .sum()
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
         let (rules, pages) = input;
-        let mut total = 0;
-        for ps in pages {
-            if !check_rules(&ps, &rules) {
-                let sorted = resort(&ps, &rules);
-                total += sorted[ps.len() / 2];
-            }
-        }
-        total
+        pages
+            .iter()
+            .filter(|ps| !is_ordered(ps, &rules))
+            .map(|ps| sort_pages(ps, &rules)[ps.len() / 2])
+            .sum()
     }
 }
 
-fn less(i: usize, j: usize) -> bool {
-    i < j
-}
-
-fn greater(i: usize, j: usize) -> bool {
-    i > j
-}
-
-fn check_rules(pages: &[usize], rules: &HashMap<usize, Vec<Rule>>) -> bool {
-    let index: HashMap<usize, usize> = pages.iter().enumerate().map(|(i, p)| (*p, i)).collect();
-    find_failing_index(&index, rules).is_none()
-}
-
-fn find_failing_index(
-    index: &HashMap<usize, usize>,
-    rules: &HashMap<usize, Vec<Rule>>,
-) -> Option<((usize, usize), (usize, usize))> {
-    index.iter().find_map(|(p, i)| {
-        let rules = rules.get(p).map(|rs| rs.as_slice()).unwrap_or(&[]);
-        rules.iter().find_map(move |(q, rule)| {
-            let j = *index.get(q)?;
-            let i = *i;
-            if rule(i, j) {
-                None
-            } else {
-                Some(((*p, i), (*q, j)))
-            }
-        })
-    })
+fn has_rule(rules: &DirectedGraph<usize>, earlier: usize, later: usize) -> bool {
+    match (rules.id_of(&earlier), rules.id_of(&later)) {
+        (Some(a), Some(b)) => rules.weight(a, b) > 0,
+        _ => false,
+    }
 }
 
-fn fix_single_rule(
-    index: &mut HashMap<usize, usize>,
-    rules: &HashMap<usize, Vec<Rule>>,
-) -> Option<()> {
-    let ((p, i), (q, j)) = find_failing_index(index, rules)?;
-    *index.get_mut(&p).unwrap() = j;
-    *index.get_mut(&q).unwrap() = i;
-    Some(())
+fn is_ordered(pages: &[usize], rules: &DirectedGraph<usize>) -> bool {
+    pairs(pages).all(|(earlier, later)| !has_rule(rules, *later, *earlier))
 }
 
-fn resort(pages: &[usize], rules: &HashMap<usize, Vec<Rule>>) -> Vec<usize> {
-    let mut index: HashMap<usize, usize> = pages.iter().enumerate().map(|(i, p)| (*p, i)).collect();
-    while fix_single_rule(&mut index, rules).is_some() {}
-    let n = pages.len();
-    let mut result = vec![0; n];
-    for (p, i) in index {
-        result[i] = p;
+// The rules as a whole contain cycles, but the rules between the pages
+// of a single update do not.
+fn sort_pages(pages: &[usize], rules: &DirectedGraph<usize>) -> Vec<usize> {
+    let mut graph = DirectedGraph::new();
+    for p in pages {
+        graph.add_node(*p);
+    }
+    for (p, q) in pairs(pages) {
+        if has_rule(rules, *p, *q) {
+            graph.add_edge(*p, *q);
+        } else if has_rule(rules, *q, *p) {
+            graph.add_edge(*q, *p);
+        }
     }
-    result
+    graph
+        .topological_labels()
+        .expect("Rules within an update are acyclic")
 }
 
 #[cfg(test)]
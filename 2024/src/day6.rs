@@ -1,10 +1,8 @@
-use {
-    aoc_core::{
-        basic_grid,
-        grid::{Grid, Position},
-        Solution,
-    },
-    std::collections::HashSet,
+use aoc_core::{
+    basic_grid,
+    bitset::VisitedGrid,
+    grid::{Grid, Position},
+    Solution,
 };
 
 pub struct Day6;
@@ -25,10 +23,10 @@ impl Solution<'_> for Day6 {
     fn part_2(mut input: Self::Input) -> Self::Output2 {
         let initial_position = get_initial_position(&input);
         let mut original_path = get_path(&input, initial_position);
-        original_path.remove(&initial_position);
+        original_path.remove(initial_position);
 
         let mut total = 0;
-        for obstacle_position in original_path {
+        for obstacle_position in original_path.positions() {
             input[obstacle_position] = b'#';
 
             if is_loop(&input, initial_position) {
@@ -47,11 +45,11 @@ fn get_initial_position(grid: &Grid<u8>) -> Position {
         .expect("Has start")
 }
 
-fn get_path(grid: &Grid<u8>, initial_position: Position) -> HashSet<Position> {
+fn get_path(grid: &Grid<u8>, initial_position: Position) -> VisitedGrid {
     let nc = grid.neighbor_context();
     let directions = nc.cardinal_directions();
 
-    let mut result = HashSet::new();
+    let mut result = VisitedGrid::for_grid(grid, 1);
     let mut guard_position = initial_position;
     let mut direction = 0;
     result.insert(guard_position);
@@ -72,10 +70,10 @@ fn is_loop(grid: &Grid<u8>, initial_position: Position) -> bool {
     let nc = grid.neighbor_context();
     let directions = nc.cardinal_directions();
 
-    let mut history: HashSet<(Position, usize)> = HashSet::new();
+    let mut history = VisitedGrid::for_grid(grid, 4);
     let mut guard_position = initial_position;
     let mut direction = 0;
-    history.insert((guard_position, direction));
+    history.insert_layer(guard_position, direction);
 
     while let Some(mut next_position) = directions[direction](guard_position) {
         while grid[next_position] == b'#' {
@@ -83,11 +81,9 @@ fn is_loop(grid: &Grid<u8>, initial_position: Position) -> bool {
             next_position = directions[direction](guard_position).unwrap();
         }
         guard_position = next_position;
-        let vector = (guard_position, direction);
-        if history.contains(&vector) {
+        if !history.insert_layer(guard_position, direction) {
             return true;
         }
-        history.insert(vector);
     }
 
     false
@@ -2,6 +2,7 @@ use {
     aoc_core::{
         basic_grid,
         grid::{signed, SignedPosition},
+        iter::pairs,
         Solution,
     },
     std::collections::{HashMap, HashSet},
@@ -55,25 +56,23 @@ fn in_bounds(x: SignedPosition, max_row: isize, max_col: isize) -> bool {
     0 <= x.0 && x.0 < max_row && 0 <= x.1 && x.1 < max_col
 }
 
+// Each unordered pair of antennas is used in both directions, as either
+// antenna can be the nearer one.
+fn directed_pairs(
+    antennas: &[SignedPosition],
+) -> impl Iterator<Item = (SignedPosition, SignedPosition)> + '_ {
+    pairs(antennas).flat_map(|(x, y)| [(*x, *y), (*y, *x)])
+}
+
 fn find_nodes(
     antennas: &[SignedPosition],
     max_row: isize,
     max_col: isize,
 ) -> HashSet<SignedPosition> {
-    let mut nodes = HashSet::new();
-    for x in antennas {
-        for y in antennas {
-            if x == y {
-                continue;
-            }
-            let dx = (y.0 - x.0, y.1 - x.1);
-            let z = (x.0 + 2 * dx.0, x.1 + 2 * dx.1);
-            if in_bounds(z, max_row, max_col) {
-                nodes.insert(z);
-            }
-        }
-    }
-    nodes
+    directed_pairs(antennas)
+        .map(|(x, y)| (2 * y.0 - x.0, 2 * y.1 - x.1))
+        .filter(|z| in_bounds(*z, max_row, max_col))
+        .collect()
 }
 
 fn find_nodes2(
@@ -82,18 +81,13 @@ fn find_nodes2(
     max_col: isize,
 ) -> HashSet<SignedPosition> {
     let mut nodes = HashSet::new();
-    for x in antennas {
-        for y in antennas {
-            if x == y {
-                continue;
-            }
-            let dx = (y.0 - x.0, y.1 - x.1);
-            let mut z = (x.0 + dx.0, x.1 + dx.1);
-            while in_bounds(z, max_row, max_col) {
-                nodes.insert(z);
-                z.0 += dx.0;
-                z.1 += dx.1;
-            }
+    for (x, y) in directed_pairs(antennas) {
+        let dx = (y.0 - x.0, y.1 - x.1);
+        let mut z = y;
+        while in_bounds(z, max_row, max_col) {
+            nodes.insert(z);
+            z.0 += dx.0;
+            z.1 += dx.1;
         }
     }
     nodes
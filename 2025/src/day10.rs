@@ -1,5 +1,10 @@
 use {
-    aoc_core::{iter::DynCartesianProduct, matrix::IntegerMatrix, Solution},
+    aoc_core::{
+        bitset::GrowableBitSet,
+        ilp::IntegerProgram,
+        parse::{delimited, lines, separated, take_while1, unsigned, Parser},
+        Solution,
+    },
     std::{
         collections::{HashSet, VecDeque},
         ops::BitXor,
@@ -14,32 +19,9 @@ impl Solution<'_> for Day10 {
     type Output2 = u32;
 
     fn parse_input(data: &'_ str) -> Self::Input {
-        data.lines()
-            .map(|l| {
-                let mut parts = l.split(' ').peekable();
-
-                let lights_schematic = parts.next().expect("Lights come first");
-                let lights = LightsState::parse(lights_schematic);
-
-                let mut buttons = Vec::new();
-                while parts.peek().is_some_and(|s| s.starts_with('(')) {
-                    let button_schematic = parts.next().expect("Is Some because we peeked");
-                    buttons.push(Button::parse(button_schematic));
-                }
-
-                let joltages = parts.next().expect("Joltages are last");
-                let n = joltages.len() - 1;
-                let joltages = joltages[1..n]
-                    .split(',')
-                    .map(|j| j.parse().expect("Joltages are numbers"))
-                    .collect();
-                Machine {
-                    target_lights: lights,
-                    buttons,
-                    joltages,
-                }
-            })
-            .collect()
+        lines(Machine::parser())
+            .parse(data)
+            .expect("Machine descriptions")
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
@@ -51,52 +33,41 @@ impl Solution<'_> for Day10 {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct LightsState(u16);
+// One bit per light, growing to fit machines of any size.
+type Lights = GrowableBitSet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LightsState(Lights);
 
 impl LightsState {
-    fn parse(schematic: &str) -> Self {
-        let lights_len = schematic.len() - 1;
-
-        let (value, _) =
-            schematic[1..lights_len]
-                .bytes()
-                .fold((0, 1), |(value, offset), c| match c {
-                    b'#' => (value ^ offset, offset << 1),
-                    b'.' => (value, offset << 1),
-                    _ => panic!("Unexpected char in lights schematic"),
-                });
-
-        Self(value)
+    // e.g. `[.##.]`
+    fn parser<'a>() -> impl Parser<'a, Self> {
+        let schematic = take_while1("lights", |c| c == '.' || c == '#');
+        delimited("[", schematic, "]")
+            .map(|lights: &str| Self(lights.match_indices('#').map(|(i, _)| i).collect()))
     }
 }
 
-impl BitXor<Button> for LightsState {
-    type Output = Self;
+impl BitXor<&Button> for &LightsState {
+    type Output = LightsState;
 
-    fn bitxor(self, rhs: Button) -> Self::Output {
-        Self(self.0 ^ rhs.0)
+    fn bitxor(self, rhs: &Button) -> Self::Output {
+        LightsState(&self.0 ^ &rhs.0)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Button(u16);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Button(Lights);
 
 impl Button {
-    fn parse(schematic: &str) -> Self {
-        let buttons_len = schematic.len() - 1;
-
-        let value = schematic[1..buttons_len].split(',').fold(0, |acc, s| {
-            let offset: u16 = s.parse().expect("Buttons described by numbers");
-            let value = 1 << offset;
-            acc ^ value
-        });
-
-        Self(value)
+    // e.g. `(1,3)`
+    fn parser<'a>() -> impl Parser<'a, Self> {
+        delimited("(", separated(unsigned(), ","), ")")
+            .map(|lights: Vec<usize>| Self(lights.into_iter().collect()))
     }
 
-    fn contains_index(&self, i: u16) -> bool {
-        self.0 & (1 << i) > 0
+    fn contains_index(&self, i: usize) -> bool {
+        self.0.contains(i)
     }
 }
 
@@ -108,10 +79,24 @@ pub struct Machine {
 }
 
 impl Machine {
+    // e.g. `[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}`
+    fn parser<'a>() -> impl Parser<'a, Self> {
+        let joltages = delimited("{", separated(unsigned(), ","), "}");
+        LightsState::parser()
+            .skip(" ")
+            .then(Button::parser().skip(" ").many())
+            .then(joltages)
+            .map(|((target_lights, buttons), joltages)| Self {
+                target_lights,
+                buttons,
+                joltages,
+            })
+    }
+
     fn lights_bfs(&self) -> u32 {
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
-        queue.push_back((LightsState(0), 0));
+        queue.push_back((LightsState(Lights::new()), 0));
 
         while let Some((state, n_presses)) = queue.pop_front() {
             if state == self.target_lights {
@@ -120,13 +105,13 @@ impl Machine {
             if visited.contains(&state) {
                 continue;
             }
-            visited.insert(state);
 
             let next_presses = n_presses + 1;
             for b in &self.buttons {
-                let new_state = state ^ *b;
+                let new_state = &state ^ b;
                 queue.push_back((new_state, next_presses));
             }
+            visited.insert(state);
         }
 
         panic!("No solution found!");
@@ -136,6 +121,61 @@ impl Machine {
         let n = self.joltages.len();
         let m = self.buttons.len();
 
+        // Minimize the total number of presses such that each joltage
+        // is reached exactly.
+        let mut program = IntegerProgram::new(vec![1; m]);
+        for i in 0..n {
+            let coefficients = self
+                .buttons
+                .iter()
+                .map(|b| b.contains_index(i) as i64)
+                .collect();
+            program = program.equality(coefficients, self.joltages[i] as i64);
+        }
+
+        // A button can be pressed at most a number of times equal to
+        // the smallest required joltage it is connected to.
+        for (j, button) in self.buttons.iter().enumerate() {
+            for (i, joltage) in self.joltages.iter().enumerate() {
+                if button.contains_index(i) {
+                    program = program.upper_bound(j, *joltage as i64);
+                }
+            }
+        }
+
+        let solution = program.solve().expect("Joltages can be reached");
+        solution.value as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        aoc_core::{iter::Odometer, matrix::IntegerMatrix},
+    };
+
+    const EXAMPLE_INPUT: &str = include_str!("res/day10_example.txt");
+
+    #[test]
+    fn test_part1() {
+        let input = Day10::parse_input(EXAMPLE_INPUT);
+        let output = Day10::part_1(input);
+        assert_eq!(output, 7);
+    }
+
+    #[test]
+    fn test_joltage_matches_brute_force() {
+        for machine in Day10::parse_input(EXAMPLE_INPUT) {
+            assert_eq!(machine.joltage_solve(), joltage_brute_force(&machine));
+        }
+    }
+
+    // Row reduce and then try every assignment of the free variables.
+    fn joltage_brute_force(machine: &Machine) -> u32 {
+        let n = machine.joltages.len();
+        let m = machine.buttons.len();
+
         // A button can be pressed at most a number of times equal to
         // the smallest required joltage it is connected to.
         let mut max_presses = vec![u32::MAX; m];
@@ -144,15 +184,15 @@ impl Machine {
         let mut matrix = IntegerMatrix {
             rows: vec![vec![0; m + 1]; n],
         };
-        for (j, button) in self.buttons.iter().enumerate() {
-            for (i, joltage) in self.joltages.iter().enumerate() {
-                if button.contains_index(i as u16) {
+        for (j, button) in machine.buttons.iter().enumerate() {
+            for (i, joltage) in machine.joltages.iter().enumerate() {
+                if button.contains_index(i) {
                     max_presses[j] = max_presses[j].min(*joltage);
                     matrix.rows[i][j] = 1;
                 }
             }
         }
-        for (i, joltage) in self.joltages.iter().enumerate() {
+        for (i, joltage) in machine.joltages.iter().enumerate() {
             matrix.rows[i][m] = (*joltage) as i64;
         }
 
@@ -172,14 +212,23 @@ impl Machine {
             return total as u32;
         }
 
-        // Brute force search over free vars state space
-        let state_space =
-            DynCartesianProduct::new(free_vars.iter().map(|j| max_presses[*j] + 1).collect())
-                .expect("All buttons can be pressed at least once");
+        // Search over free vars state space
+        let mut state_space =
+            Odometer::new(free_vars.iter().map(|j| max_presses[*j] + 1).collect());
 
         let mut min_presses = u32::MAX;
-        'outer: for values in state_space {
-            let mut total_presses: u32 = values.iter().copied().sum();
+        'outer: while let Some(values) = state_space.next() {
+            // Presses are never negative, so once the free vars alone reach the
+            // best total there is no point in increasing them any further.
+            let mut prefix_presses = 0;
+            for (k, x) in values.iter().enumerate() {
+                prefix_presses += x;
+                if prefix_presses >= min_presses {
+                    state_space.skip_prefix(k);
+                    continue 'outer;
+                }
+            }
+            let mut total_presses = prefix_presses;
             for var in &fixed_vars {
                 // Only accept whole number presses
                 let free_values = values.iter().map(|x| *x as i64);
@@ -202,20 +251,6 @@ impl Machine {
 
         min_presses
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_INPUT: &str = include_str!("res/day10_example.txt");
-
-    #[test]
-    fn test_part1() {
-        let input = Day10::parse_input(EXAMPLE_INPUT);
-        let output = Day10::part_1(input);
-        assert_eq!(output, 7);
-    }
 
     #[test]
     fn test_part2() {
@@ -1,70 +1,35 @@
-use {aoc_core::Solution, std::collections::HashMap};
+use aoc_core::{graph::DirectedGraph, Solution};
 
 pub struct Day11;
 
 impl<'a> Solution<'a> for Day11 {
-    type Input = HashMap<&'a str, Vec<&'a str>>;
+    type Input = DirectedGraph<&'a str>;
     type Output1 = usize;
     type Output2 = usize;
 
     fn parse_input(data: &'a str) -> Self::Input {
-        data.lines()
-            .map(|l| {
-                let (label, neighbors) = l.split_once(':').expect("Has colon");
-                let neighbors: Vec<&'a str> = neighbors.trim().split(' ').collect();
-                (label, neighbors)
-            })
-            .collect()
+        DirectedGraph::unweighted(data.lines().map(|l| {
+            let (label, neighbors) = l.split_once(':').expect("Has colon");
+            (label, neighbors.trim().split(' '))
+        }))
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
         // Can ignore `dac` and `fft` in part 1.
-        memoized_paths_to_out(("you", true, true), &input, &mut HashMap::new())
+        paths_to_out(&input, "you", &[])
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        memoized_paths_to_out(("svr", false, false), &input, &mut HashMap::new())
+        paths_to_out(&input, "svr", &["dac", "fft"])
     }
 }
 
-type MemoInput<'a> = (&'a str, bool, bool);
-
-fn memoized_paths_to_out<'a>(
-    x: MemoInput<'a>,
-    graph: &HashMap<&'a str, Vec<&'a str>>,
-    memo: &mut HashMap<MemoInput<'a>, usize>,
-) -> usize {
-    if let Some(result) = memo.get(&x) {
-        return *result;
-    }
-
-    let (source, mut has_dac, mut has_fft) = x;
-
-    match source {
-        "out" if has_dac && has_fft => {
-            return 1;
-        }
-        "out" => {
-            return 0;
-        }
-        "dac" => {
-            has_dac = true;
-        }
-        "fft" => {
-            has_fft = true;
-        }
-        _ => (),
-    }
-
-    let mut total = 0;
-    if let Some(neighbors) = graph.get(source) {
-        for &next_node in neighbors {
-            let x = (next_node, has_dac, has_fft);
-            total += memoized_paths_to_out(x, graph, memo);
-        }
-    }
-    memo.insert(x, total);
-    total
+fn paths_to_out(graph: &DirectedGraph<&str>, source: &str, via: &[&str]) -> usize {
+    let id = |x: &str| graph.id_of(&x).expect("Node exists");
+    let via: Vec<usize> = via.iter().map(|x| id(x)).collect();
+    graph
+        .count_paths_through(id(source), id("out"), &via)
+        .expect("Graph is acyclic") as usize
 }
 
 #[cfg(test)]
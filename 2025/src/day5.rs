@@ -1,4 +1,7 @@
-use {aoc_core::Solution, std::ops::RangeInclusive};
+use {
+    aoc_core::{intervals::IntervalSet, Solution},
+    std::ops::RangeInclusive,
+};
 
 pub struct Day5;
 
@@ -33,26 +36,14 @@ impl Solution<'_> for Day5 {
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let (mut ranges, _) = input;
+        let (ranges, _) = input;
 
-        // Sort ranges in descending order so that we can pop off the end
-        // to get them in order from smallest to largest
-        ranges.sort_by(|a, b| b.start().cmp(a.start()));
-
-        let mut total = 0;
-        let mut last_fresh_id = 0;
-
-        while let Some(range) = ranges.pop() {
-            let end = *range.end();
-            if end <= last_fresh_id {
-                continue;
-            }
-            let start = (*range.start()).max(last_fresh_id + 1);
-            total += end - start + 1;
-            last_fresh_id = end;
+        // Overlapping ranges are merged so each fresh ID is only counted once.
+        let mut fresh = IntervalSet::new();
+        for range in ranges {
+            fresh.insert_inclusive(range);
         }
-
-        total
+        fresh.len()
     }
 }
 
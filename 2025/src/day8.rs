@@ -1,5 +1,5 @@
 use {
-    aoc_core::{min_heap::MinHeap, Solution},
+    aoc_core::{iter::pairs, min_heap::MinHeap, min_max, vector::Vec3, Solution},
     std::collections::BTreeSet,
 };
 
@@ -11,7 +11,9 @@ impl Solution<'_> for Day8 {
     type Output2 = u64;
 
     fn parse_input(data: &'_ str) -> Self::Input {
-        data.lines().map(Point3d::parse_line).collect()
+        data.lines()
+            .map(|line| line.parse().expect("Coordinates are numbers"))
+            .collect()
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
@@ -49,18 +51,11 @@ fn connect_shortest_distances(input: &[Point3d], n_connections: usize) -> usize
 fn build_dist_heap(input: &[Point3d]) -> MinHeap<(u64, &Point3d, &Point3d)> {
     let mut dist_heap = MinHeap::new();
 
-    // Construct all the distance pairs.
-    for pt1 in input {
-        for pt2 in input {
-            // Only record a pair if pt1 < pt2
-            // since the distance function is symmetric.
-            if pt2 <= pt1 {
-                continue;
-            }
-
-            let d = pt1.dist_sq(pt2);
-            dist_heap.push((d, pt1, pt2));
-        }
+    // Construct all the distance pairs, ordered so that pt1 < pt2.
+    for (a, b) in pairs(input) {
+        let (pt1, pt2) = min_max(a, b);
+        let d = pt1.squared_distance(pt2);
+        dist_heap.push((d, pt1, pt2));
     }
 
     dist_heap
@@ -83,13 +78,13 @@ fn next_connection<'a>(
     match maybe_existing {
         None => {
             let mut new_circuit = BTreeSet::new();
-            new_circuit.insert(pt1.clone());
-            new_circuit.insert(pt2.clone());
+            new_circuit.insert(*pt1);
+            new_circuit.insert(*pt2);
             circuits.push(new_circuit);
         }
         Some(existing) => {
-            existing.insert(pt1.clone());
-            existing.insert(pt2.clone());
+            existing.insert(*pt1);
+            existing.insert(*pt2);
 
             // Since two circuits were merged into one then we should remove the empty one.
             circuits.retain(|c| !c.is_empty());
@@ -99,34 +94,7 @@ fn next_connection<'a>(
     (pt1, pt2)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Point3d {
-    x: u64,
-    y: u64,
-    z: u64,
-}
-
-impl Point3d {
-    fn parse_line(line: &str) -> Self {
-        let mut coords = [0_u64; 3];
-        for (a, b) in coords.iter_mut().zip(line.split(',')) {
-            *a = b.parse().expect("Coordinates are numbers");
-        }
-        Self {
-            x: coords[0],
-            y: coords[1],
-            z: coords[2],
-        }
-    }
-
-    fn dist_sq(&self, other: &Self) -> u64 {
-        let x = aoc_core::abs_diff(self.x, other.x);
-        let y = aoc_core::abs_diff(self.y, other.y);
-        let z = aoc_core::abs_diff(self.z, other.z);
-
-        x * x + y * y + z * z
-    }
-}
+pub type Point3d = Vec3<u64>;
 
 #[cfg(test)]
 mod tests {
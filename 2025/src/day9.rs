@@ -1,4 +1,9 @@
-use aoc_core::{grid::Position, Solution};
+use aoc_core::{
+    geometry::{Point, RectilinearPolygon},
+    grid::Position,
+    iter::pairs,
+    Solution,
+};
 
 pub struct Day9;
 
@@ -20,108 +25,46 @@ impl Solution<'_> for Day9 {
     }
 
     fn part_1(input: Self::Input) -> Self::Output1 {
-        let mut max_area = 0;
-
-        for a in &input {
-            for b in &input {
-                // The area function is symmetric, so we only
-                // consider the pairs with a < b.
-                if b <= a {
-                    continue;
-                }
-                let x = aoc_core::abs_diff(a.0, b.0);
-                let y = aoc_core::abs_diff(a.1, b.1);
-                let area = (x + 1) * (y + 1);
-                if area > max_area {
-                    max_area = area;
-                }
-            }
-        }
-
-        max_area
+        // The area function is symmetric, so each unordered pair is enough.
+        pairs(&input)
+            .map(|(a, b)| Rectangle::new(*a, *b).area())
+            .max()
+            .unwrap_or(0)
     }
 
     fn part_2(input: Self::Input) -> Self::Output2 {
-        let n = input.len();
-        let mut rectangles = Vec::with_capacity(input.len() * input.len() / 2);
-        let mut ymax = 0;
-
-        // Construct all rectangles and look for the largest y coordinate of any corner.
-        // The max y coordinate is used to allocate memory for the edges matrix later.
-        for a in &input {
-            if a.1 > ymax {
-                ymax = a.1;
-            }
-
-            for b in &input {
-                if b <= a {
-                    continue;
-                }
-
-                let rectangle = Rectangle::new(*a, *b);
-                rectangles.push(rectangle);
-            }
-        }
+        // Construct all rectangles.
+        let mut rectangles: Vec<Rectangle> =
+            pairs(&input).map(|(a, b)| Rectangle::new(*a, *b)).collect();
 
         // Sort rectangles by area (decreasing)
         rectangles.sort_by_key(|r| std::cmp::Reverse(r.area()));
 
-        // Record edges of polygon as a sparse matrix.
-        // At each y coordinate only store the x coordinates that belong to edges
-        let mut polygon: Vec<Vec<usize>> = vec![Vec::new(); ymax + 1];
-        for (i, a) in input.iter().enumerate() {
-            // Edges of the polygon are defined by neighboring inputs.
-            let b = &input[(i + 1) % n];
-
-            let (xmin, xmax) = aoc_core::min_max(a.0, b.0);
-            let (ymin, ymax) = aoc_core::min_max(a.1, b.1);
-
-            if xmin == xmax {
-                // Vertical edge has points on many y values
-                for xcoords in polygon.iter_mut().take(ymax + 1).skip(ymin) {
-                    xcoords.push(xmin);
-                }
-            } else if ymin == ymax {
-                // Horizontal edge has points on many x values
-                for x in xmin..=xmax {
-                    polygon[ymin].push(x);
-                }
-            } else {
-                panic!("All edges are horizontal or vertical");
-            }
-        }
+        // Edges of the polygon are defined by neighboring inputs.
+        let vertices: Vec<Point> = input
+            .iter()
+            .map(|p| Point::new(p.0 as i64, p.1 as i64))
+            .collect();
+        let polygon =
+            RectilinearPolygon::new(&vertices).expect("All edges are horizontal or vertical");
 
         // Look for the first rectangle (aka largest area thanks to the sorting)
         // that fits in the polygon
         let largest = rectangles
             .iter()
-            .find(|rectangle| contains(&polygon, rectangle))
+            .find(|rectangle| {
+                let (x1, y1) = rectangle.upper_left_corner;
+                let (x2, y2) = rectangle.lower_right_corner;
+                polygon.contains_rectangle(
+                    Point::new(x1 as i64, y1 as i64),
+                    Point::new(x2 as i64, y2 as i64),
+                )
+            })
             .expect("There is a solution");
         largest.area()
     }
 }
 
-// Check if the given rectangle fits inside the polygon by
-// comparing against the sparse matrix of edges.
-// If a side of the rectangle intersects one of the edges of the polygon
-// then the rectangle is not contained.
-fn contains(polygon: &[Vec<usize>], rectangle: &Rectangle) -> bool {
-    let (xmin, ymin) = rectangle.upper_left_corner;
-    let (xmax, ymax) = rectangle.lower_right_corner;
-
-    // Only need to check the y values that the rectangle spans
-    for xcoords in polygon.iter().take(ymax).skip(ymin + 1) {
-        for &edge_point in xcoords {
-            // The side of the rectangle intersects an edge of the polygon
-            if xmin < edge_point && edge_point < xmax {
-                return false;
-            }
-        }
-    }
-
-    true
-}
-
 #[derive(Debug)]
 struct Rectangle {
     upper_left_corner: Position,
@@ -0,0 +1,440 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    str::FromStr,
+};
+
+// Arbitrary precision integer for when intermediate values do not fit in 128 bits.
+// The magnitude is stored as base 2^32 digits, least significant first, with no
+// trailing zeros (so zero is the empty vector, and is never negative).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            negative: false,
+            magnitude: self.magnitude.clone(),
+        }
+    }
+
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    // Truncated division (quotient rounds towards zero, remainder has the sign of
+    // `self`), matching the behaviour of the primitive integer types.
+    // Panics if `other` is zero.
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        assert!(!other.is_zero(), "Division by zero");
+        let (q, r) = magnitude_div_rem(&self.magnitude, &other.magnitude);
+        (
+            Self::from_parts(self.negative != other.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::from(1_u64);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    // Returns `None` if the value does not fit.
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0_u128, |acc, d| (acc << 32) | (*d as u128));
+        if self.negative {
+            0_i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(mut value: u128) -> Self {
+        let mut magnitude = Vec::new();
+        while value > 0 {
+            magnitude.push(value as u32);
+            value >>= 32;
+        }
+        Self::from_parts(false, magnitude)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let mut result = Self::from(value.unsigned_abs());
+        result.negative = value < 0;
+        result
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        Self::from(value as u128)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        // Consume 9 decimal digits at a time so each chunk fits in a u32.
+        let mut magnitude = Vec::new();
+        let head = digits.len() % 9;
+        let chunks = std::iter::once(&digits[..head])
+            .filter(|c| !c.is_empty())
+            .chain(
+                digits.as_bytes()[head..]
+                    .chunks(9)
+                    .map(|c| std::str::from_utf8(c).expect("Digits are ASCII")),
+            );
+        for chunk in chunks {
+            let value: u32 = chunk.parse().map_err(|_| ParseBigIntError)?;
+            let scale = 10_u32.pow(chunk.len() as u32);
+            magnitude_mul_small_add(&mut magnitude, scale, value);
+        }
+        Ok(Self::from_parts(negative, magnitude))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // Repeatedly divide by 10^9 to get base 10^9 digits, least significant first.
+        const CHUNK: u32 = 1_000_000_000;
+        let mut chunks = Vec::new();
+        let mut remaining = self.magnitude.clone();
+        while !remaining.is_empty() {
+            chunks.push(magnitude_div_small(&mut remaining, CHUNK));
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut iter = chunks.iter().rev();
+        write!(f, "{}", iter.next().expect("Non-zero"))?;
+        for chunk in iter {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => magnitude_cmp(&self.magnitude, &other.magnitude),
+            (true, true) => magnitude_cmp(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(
+                self.negative,
+                magnitude_add(&self.magnitude, &rhs.magnitude),
+            );
+        }
+        // Opposite signs: subtract the smaller magnitude from the larger one.
+        match magnitude_cmp(&self.magnitude, &rhs.magnitude) {
+            Ordering::Equal => BigInt::zero(),
+            Ordering::Greater => BigInt::from_parts(
+                self.negative,
+                magnitude_sub(&self.magnitude, &rhs.magnitude),
+            ),
+            Ordering::Less => {
+                BigInt::from_parts(rhs.negative, magnitude_sub(&rhs.magnitude, &self.magnitude))
+            }
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &(-rhs.clone())
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = vec![0_u32; self.magnitude.len() + rhs.magnitude.len()];
+        for (i, a) in self.magnitude.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, b) in rhs.magnitude.iter().enumerate() {
+                let t = (*a as u64) * (*b as u64) + (result[i + j] as u64) + carry;
+                result[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            result[i + rhs.magnitude.len()] = carry as u32;
+        }
+        BigInt::from_parts(self.negative != rhs.negative, result)
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+// Forward the owned versions of the operators to the borrowed ones.
+macro_rules! impl_owned_op {
+    ($($trait:ident, $method:ident);*) => {
+        $(
+            impl $trait for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, rhs: Self) -> Self::Output {
+                    (&self).$method(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_op!(Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem);
+
+fn magnitude_cmp(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn magnitude_add(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0_u64;
+    for (i, x) in long.iter().enumerate() {
+        let t = (*x as u64) + (short.get(i).copied().unwrap_or_default() as u64) + carry;
+        result.push(t as u32);
+        carry = t >> 32;
+    }
+    result.push(carry as u32);
+    result
+}
+
+// Requires `a >= b`.
+fn magnitude_sub(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0_i64;
+    for (i, x) in a.iter().enumerate() {
+        let mut t = (*x as i64) - (b.get(i).copied().unwrap_or_default() as i64) - borrow;
+        borrow = 0;
+        if t < 0 {
+            t += 1 << 32;
+            borrow = 1;
+        }
+        result.push(t as u32);
+    }
+    debug_assert_eq!(borrow, 0, "Subtraction underflow");
+    result
+}
+
+fn magnitude_mul_small_add(a: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for x in a.iter_mut() {
+        let t = (*x as u64) * (factor as u64) + carry;
+        *x = t as u32;
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        a.push(carry as u32);
+    }
+}
+
+// Divide in place, returning the remainder. Trailing zeros are removed.
+fn magnitude_div_small(a: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0_u64;
+    for x in a.iter_mut().rev() {
+        let t = (remainder << 32) | (*x as u64);
+        *x = (t / divisor as u64) as u32;
+        remainder = t % divisor as u64;
+    }
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+    remainder as u32
+}
+
+// Binary long division. Simple rather than fast, but the numbers involved
+// in puzzles are not large enough for that to matter.
+fn magnitude_div_rem(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if b.len() == 1 {
+        let mut q = a.to_vec();
+        let r = magnitude_div_small(&mut q, b[0]);
+        return (q, vec![r]);
+    }
+
+    let mut quotient = vec![0_u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for i in (0..(a.len() * 32)).rev() {
+        // remainder = 2 * remainder + next bit of a
+        let bit = (a[i / 32] >> (i % 32)) & 1;
+        magnitude_mul_small_add(&mut remainder, 2, bit);
+        if magnitude_cmp(&remainder, b) != Ordering::Less {
+            remainder = magnitude_sub(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for s in [
+            "0",
+            "7",
+            "-42",
+            "1000000000",
+            "123456789012345678901234567890123456789",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("-0"), BigInt::zero());
+        assert_eq!(big("+00012").to_string(), "12");
+        assert!("12a".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic_matches_i128() {
+        let values: [i128; 7] = [
+            0,
+            1,
+            -1,
+            12345,
+            -987654321,
+            i64::MAX as i128,
+            i64::MIN as i128,
+        ];
+        for a in values {
+            for b in values {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!((&x + &y).to_i128(), Some(a + b));
+                assert_eq!((&x - &y).to_i128(), Some(a - b));
+                assert_eq!((&x * &y).to_i128(), Some(a * b));
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                if b != 0 {
+                    let (q, r) = x.div_rem(&y);
+                    assert_eq!(q.to_i128(), Some(a / b));
+                    assert_eq!(r.to_i128(), Some(a % b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_values() {
+        let x = BigInt::from(2_u64).pow(200);
+        assert_eq!(
+            x.to_string(),
+            "1606938044258990275541962092341162602522202993782792835301376"
+        );
+        assert_eq!(x.to_i128(), None);
+        let y = big("340282366920938463463374607431768211457");
+        let (q, r) = x.div_rem(&y);
+        assert_eq!(&(&q * &y) + &r, x);
+        assert!(r < y);
+    }
+}
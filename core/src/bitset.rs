@@ -0,0 +1,504 @@
+use {
+    crate::grid::{Grid, Position},
+    std::{
+        fmt,
+        hash::{Hash, Hasher},
+        ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub},
+    },
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// Indices of the set bits of `words`, in increasing order.
+fn ones(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(i, w)| {
+        let mut w = *w;
+        std::iter::from_fn(move || {
+            if w == 0 {
+                return None;
+            }
+            let bit = w.trailing_zeros() as usize;
+            w &= w - 1;
+            Some(i * WORD_BITS + bit)
+        })
+    })
+}
+
+// Set of integers below `64 * WORDS`, stored inline. Panics on values which
+// do not fit rather than dropping them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    pub const CAPACITY: usize = WORDS * WORD_BITS;
+
+    pub const fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    // Returns whether `a` was not already in the set.
+    pub fn insert(&mut self, a: usize) -> bool {
+        let (word, mask) = Self::locate(a);
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    // Returns whether `a` was in the set.
+    pub fn remove(&mut self, a: usize) -> bool {
+        let (word, mask) = Self::locate(a);
+        let removed = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        removed
+    }
+
+    pub fn toggle(&mut self, a: usize) {
+        let (word, mask) = Self::locate(a);
+        self.words[word] ^= mask;
+    }
+
+    pub fn contains(&self, a: usize) -> bool {
+        a < Self::CAPACITY && self.words[a / WORD_BITS] & (1 << (a % WORD_BITS)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(&self.words)
+    }
+
+    fn locate(a: usize) -> (usize, u64) {
+        assert!(a < Self::CAPACITY, "{a} does not fit in a BitSet<{WORDS}>");
+        (a / WORD_BITS, 1 << (a % WORD_BITS))
+    }
+}
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize> fmt::Debug for BitSet<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for a in iter {
+            result.insert(a);
+        }
+        result
+    }
+}
+
+// Word-wise set operations for the fixed width sets.
+macro_rules! impl_bit_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, $symbol:tt) => {
+        impl<const WORDS: usize> $assign for BitSet<WORDS> {
+            fn $assign_fn(&mut self, rhs: Self) {
+                for (a, b) in self.words.iter_mut().zip(rhs.words) {
+                    *a = *a $symbol b;
+                }
+            }
+        }
+
+        impl<const WORDS: usize> $op for BitSet<WORDS> {
+            type Output = Self;
+
+            fn $op_fn(mut self, rhs: Self) -> Self::Output {
+                self.$assign_fn(rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+// Set of integers which grows to fit whatever is inserted.
+#[derive(Clone, Default)]
+pub struct GrowableBitSet {
+    words: Vec<u64>,
+}
+
+impl GrowableBitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Preallocates room for the values below `n`.
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            words: vec![0; n.div_ceil(WORD_BITS)],
+        }
+    }
+
+    // The values `0..n`.
+    pub fn full(n: usize) -> Self {
+        let mut words = vec![u64::MAX; n / WORD_BITS];
+        if !n.is_multiple_of(WORD_BITS) {
+            words.push((1 << (n % WORD_BITS)) - 1);
+        }
+        Self { words }
+    }
+
+    // Returns whether `a` was not already in the set.
+    pub fn insert(&mut self, a: usize) -> bool {
+        let word = a / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let mask = 1 << (a % WORD_BITS);
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    // Returns whether `a` was in the set.
+    pub fn remove(&mut self, a: usize) -> bool {
+        let Some(w) = self.words.get_mut(a / WORD_BITS) else {
+            return false;
+        };
+        let mask = 1 << (a % WORD_BITS);
+        let removed = *w & mask != 0;
+        *w &= !mask;
+        removed
+    }
+
+    pub fn toggle(&mut self, a: usize) {
+        if !self.remove(a) {
+            self.insert(a);
+        }
+    }
+
+    pub fn contains(&self, a: usize) -> bool {
+        self.words
+            .get(a / WORD_BITS)
+            .is_some_and(|w| w & (1 << (a % WORD_BITS)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    // Removes every value but keeps the allocation.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(&self.words)
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+
+    // Removes every value less than or equal to `a`.
+    pub fn remove_up_to(&mut self, a: usize) {
+        let word = a / WORD_BITS;
+        let n = self.words.len().min(word);
+        self.words[..n].fill(0);
+        if let Some(w) = self.words.get_mut(word) {
+            let bit = a % WORD_BITS;
+            *w &= if bit == WORD_BITS - 1 {
+                0
+            } else {
+                u64::MAX << (bit + 1)
+            };
+        }
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+
+    // The words without trailing zeros, so that equal sets compare equal
+    // however much room they have allocated.
+    fn significant_words(&self) -> &[u64] {
+        let n = self
+            .words
+            .iter()
+            .rposition(|w| *w != 0)
+            .map_or(0, |i| i + 1);
+        &self.words[..n]
+    }
+}
+
+impl PartialEq for GrowableBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant_words() == other.significant_words()
+    }
+}
+
+impl Eq for GrowableBitSet {}
+
+impl Hash for GrowableBitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant_words().hash(state);
+    }
+}
+
+impl fmt::Debug for GrowableBitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for GrowableBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for a in iter {
+            result.insert(a);
+        }
+        result
+    }
+}
+
+impl BitAnd for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.intersect_with(rhs);
+        result
+    }
+}
+
+impl BitOr for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.union_with(rhs);
+        result
+    }
+}
+
+impl Sub for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.difference_with(rhs);
+        result
+    }
+}
+
+impl BitXor for &GrowableBitSet {
+    type Output = GrowableBitSet;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result.symmetric_difference_with(rhs);
+        result
+    }
+}
+
+// Set of grid positions, optionally paired with one of `layers` extra
+// states (e.g. a direction), stored as one bit per pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisitedGrid {
+    n_rows: usize,
+    n_cols: usize,
+    layers: usize,
+    bits: GrowableBitSet,
+}
+
+impl VisitedGrid {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        Self::with_layers(n_rows, n_cols, 1)
+    }
+
+    pub fn with_layers(n_rows: usize, n_cols: usize, layers: usize) -> Self {
+        Self {
+            n_rows,
+            n_cols,
+            layers,
+            bits: GrowableBitSet::with_capacity(n_rows * n_cols * layers),
+        }
+    }
+
+    // Same shape as `grid`.
+    pub fn for_grid<T>(grid: &Grid<T>, layers: usize) -> Self {
+        Self::with_layers(grid.n_rows(), grid.n_cols(), layers)
+    }
+
+    fn index(&self, (row, col): Position, layer: usize) -> usize {
+        assert!(
+            row < self.n_rows && col < self.n_cols && layer < self.layers,
+            "({row}, {col}) layer {layer} is outside the grid"
+        );
+        (row * self.n_cols + col) * self.layers + layer
+    }
+
+    // Returns whether the position was not already visited.
+    pub fn insert(&mut self, position: Position) -> bool {
+        self.insert_layer(position, 0)
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.contains_layer(position, 0)
+    }
+
+    pub fn remove(&mut self, position: Position) -> bool {
+        let i = self.index(position, 0);
+        self.bits.remove(i)
+    }
+
+    pub fn insert_layer(&mut self, position: Position, layer: usize) -> bool {
+        let i = self.index(position, layer);
+        self.bits.insert(i)
+    }
+
+    pub fn contains_layer(&self, position: Position, layer: usize) -> bool {
+        self.bits.contains(self.index(position, layer))
+    }
+
+    // Number of visited `(position, layer)` pairs.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    // Positions visited in any layer, in row major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        let mut last = None;
+        self.bits.iter().filter_map(move |i| {
+            let cell = i / self.layers;
+            if last == Some(cell) {
+                return None;
+            }
+            last = Some(cell);
+            Some((cell / self.n_cols, cell % self.n_cols))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::collections::HashSet};
+
+    #[test]
+    fn test_bitset() {
+        let mut a: BitSet<2> = [1, 5, 64, 127].into_iter().collect();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(64) && !a.contains(63) && !a.contains(500));
+        assert!(!a.insert(5));
+        assert!(a.remove(5));
+        assert!(!a.remove(5));
+        a.toggle(0);
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![0, 1, 64, 127]);
+
+        let b: BitSet<2> = [1, 2, 64].into_iter().collect();
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![1, 64]);
+        assert_eq!((a | b).len(), 5);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![0, 2, 127]);
+        assert!((a ^ a).is_empty());
+        assert_eq!(format!("{b:?}"), "{1, 2, 64}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bitset_overflow() {
+        BitSet::<1>::new().insert(64);
+    }
+
+    #[test]
+    fn test_growable_bitset() {
+        let mut a = GrowableBitSet::new();
+        assert!(a.insert(1000));
+        assert!(a.insert(3));
+        assert!(a.contains(1000) && !a.contains(999) && !a.contains(5000));
+        assert!(!a.remove(5000));
+        let b: GrowableBitSet = [3, 4].into_iter().collect();
+        assert_eq!((&a & &b).iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!((&a | &b).len(), 3);
+        assert_eq!((&a ^ &b).iter().collect::<Vec<_>>(), vec![4, 1000]);
+        assert_eq!((&a - &b).iter().collect::<Vec<_>>(), vec![1000]);
+
+        let mut full = GrowableBitSet::full(130);
+        assert_eq!(full.len(), 130);
+        assert!(full.contains(129) && !full.contains(130));
+        full.remove_up_to(63);
+        assert_eq!(full.iter().next(), Some(64));
+        full.remove_up_to(500);
+        assert!(full.is_empty());
+        assert_eq!(GrowableBitSet::full(128).len(), 128);
+
+        // Equality and hashing ignore unused capacity.
+        a.remove(1000);
+        let c: GrowableBitSet = [3].into_iter().collect();
+        assert_eq!(a, c);
+        let set: HashSet<GrowableBitSet> = [a, c].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_visited_grid() {
+        let mut visited = VisitedGrid::with_layers(3, 4, 4);
+        assert!(visited.insert_layer((1, 2), 3));
+        assert!(visited.insert_layer((1, 2), 0));
+        assert!(!visited.insert_layer((1, 2), 3));
+        assert!(visited.insert_layer((2, 3), 1));
+        assert!(visited.insert_layer((0, 0), 2));
+        assert_eq!(visited.len(), 4);
+        assert!(visited.contains_layer((2, 3), 1) && !visited.contains_layer((2, 3), 2));
+        assert_eq!(
+            visited.positions().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 2), (2, 3)]
+        );
+    }
+}
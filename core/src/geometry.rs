@@ -0,0 +1,332 @@
+use {
+    crate::{num::gcd, vector::Vec2},
+    std::cmp::Ordering,
+};
+
+// Polygons are given as a list of vertices in order, where the last vertex
+// connects back to the first. Repeating the first vertex at the end is allowed
+// since the zero length edge it creates does not change any of the results.
+pub type Point = Vec2<i64>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn edges(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    let n = polygon.len();
+    (0..n).map(move |i| (polygon[i], polygon[(i + 1) % n]))
+}
+
+// Twice the signed area of the polygon: positive if the vertices go
+// counter-clockwise and negative if they go clockwise. Doubling keeps it an integer.
+// See https://en.wikipedia.org/wiki/Shoelace_formula
+pub fn twice_signed_area(polygon: &[Point]) -> i64 {
+    edges(polygon).map(|(a, b)| a.cross(&b)).sum()
+}
+
+// Number of integer points on the boundary of the polygon, including the vertices.
+pub fn boundary_points(polygon: &[Point]) -> i64 {
+    edges(polygon)
+        .map(|(a, b)| {
+            let d = b - a;
+            gcd(d.x, d.y)
+        })
+        .sum()
+}
+
+// Number of integer points strictly inside a simple polygon with integer vertices.
+// See https://en.wikipedia.org/wiki/Pick%27s_theorem
+pub fn interior_points(polygon: &[Point]) -> i64 {
+    // A = I + B/2 - 1, so 2I = 2A - B + 2
+    (twice_signed_area(polygon).abs() - boundary_points(polygon) + 2) / 2
+}
+
+// Sign of the cross product `(b - a) x (c - a)`: `Greater` if `a`, `b`, `c`
+// turn counter-clockwise, `Less` if clockwise and `Equal` if collinear.
+pub fn orientation(a: Point, b: Point, c: Point) -> Ordering {
+    (b - a).cross(&(c - a)).cmp(&0)
+}
+
+fn on_segment(p: Point, a: Point, b: Point) -> bool {
+    orientation(a, b, p) == Ordering::Equal
+        && a.x.min(b.x) <= p.x
+        && p.x <= a.x.max(b.x)
+        && a.y.min(b.y) <= p.y
+        && p.y <= a.y.max(b.y)
+}
+
+// Whether the closed segments `[p1, p2]` and `[q1, q2]` share at least one point,
+// including touching at an endpoint or overlapping along a line.
+pub fn segments_intersect(p1: Point, p2: Point, q1: Point, q2: Point) -> bool {
+    let o1 = orientation(p1, p2, q1);
+    let o2 = orientation(p1, p2, q2);
+    let o3 = orientation(q1, q2, p1);
+    let o4 = orientation(q1, q2, p2);
+    // Each segment has endpoints on both sides of the other's line.
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+    // Otherwise they can only meet if some endpoint lies on the other segment.
+    on_segment(q1, p1, p2)
+        || on_segment(q2, p1, p2)
+        || on_segment(p1, q1, q2)
+        || on_segment(p2, q1, q2)
+}
+
+// Number of times the polygon winds counter-clockwise around `point`.
+// The result is meaningless if `point` is on the boundary.
+// See https://en.wikipedia.org/wiki/Point_in_polygon#Winding_number_algorithm
+pub fn winding_number(point: Point, polygon: &[Point]) -> i64 {
+    let mut total = 0;
+    for (a, b) in edges(polygon) {
+        if a.y <= point.y {
+            if b.y > point.y && orientation(a, b, point) == Ordering::Greater {
+                total += 1;
+            }
+        } else if b.y <= point.y && orientation(a, b, point) == Ordering::Less {
+            total -= 1;
+        }
+    }
+    total
+}
+
+// Locate `point` relative to a simple polygon by casting a ray in the +x direction.
+// See https://en.wikipedia.org/wiki/Point_in_polygon#Ray_casting_algorithm
+pub fn ray_cast(point: Point, polygon: &[Point]) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        if on_segment(point, a, b) {
+            return Location::Boundary;
+        }
+        // Half-open rule so a ray through a vertex is only counted once.
+        if (a.y > point.y) != (b.y > point.y) {
+            // The ray crosses if the edge is to the right of the point.
+            let side = orientation(a, b, point);
+            let crosses = if b.y > a.y {
+                side == Ordering::Greater
+            } else {
+                side == Ordering::Less
+            };
+            inside ^= crosses;
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+// Polygon whose edges are all horizontal or vertical, preprocessed so that
+// axis aligned rectangles can be tested for containment in constant time.
+// Coordinates are compressed, so the cost depends on the number of vertices
+// rather than the size of the coordinates.
+#[derive(Debug, Clone)]
+pub struct RectilinearPolygon {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    // `covered[i][j]` counts the compressed cells in rows `< i` and columns `< j`
+    // which are inside the polygon or on its boundary.
+    covered: Vec<Vec<u32>>,
+}
+
+impl RectilinearPolygon {
+    // Returns `None` if the polygon is empty or some edge is not horizontal or vertical.
+    pub fn new(polygon: &[Point]) -> Option<Self> {
+        if polygon.is_empty() || edges(polygon).any(|(a, b)| a.x != b.x && a.y != b.y) {
+            return None;
+        }
+
+        let mut xs: Vec<i64> = polygon.iter().map(|p| p.x).collect();
+        let mut ys: Vec<i64> = polygon.iter().map(|p| p.y).collect();
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+
+        // Compressed index `2k` is the coordinate `xs[k]` itself and
+        // `2k + 1` is the open interval between `xs[k]` and `xs[k + 1]`.
+        let n_cols = 2 * xs.len() - 1;
+        let n_rows = 2 * ys.len() - 1;
+        let x_index = |x: i64| 2 * xs.binary_search(&x).expect("Vertex coordinate");
+        let y_index = |y: i64| 2 * ys.binary_search(&y).expect("Vertex coordinate");
+
+        let mut cells = vec![vec![false; n_cols]; n_rows];
+        let mut crossings = vec![vec![false; n_cols]; n_rows];
+        for (a, b) in edges(polygon) {
+            let (i1, i2) = (
+                y_index(a.y).min(y_index(b.y)),
+                y_index(a.y).max(y_index(b.y)),
+            );
+            let (j1, j2) = (
+                x_index(a.x).min(x_index(b.x)),
+                x_index(a.x).max(x_index(b.x)),
+            );
+            for row in &mut cells[i1..=i2] {
+                row[j1..=j2].fill(true);
+            }
+            // Vertical edges cross the rows in their half-open span.
+            if j1 == j2 {
+                for row in &mut crossings[i1..i2] {
+                    row[j1] ^= true;
+                }
+            }
+        }
+
+        // Scan each row, filling in the cells between crossings.
+        for (row, crossing) in cells.iter_mut().zip(&crossings) {
+            let mut inside = false;
+            for (cell, crosses) in row.iter_mut().zip(crossing) {
+                inside ^= crosses;
+                *cell |= inside;
+            }
+        }
+
+        let mut covered = vec![vec![0; n_cols + 1]; n_rows + 1];
+        for i in 0..n_rows {
+            for j in 0..n_cols {
+                covered[i + 1][j + 1] =
+                    covered[i][j + 1] + covered[i + 1][j] - covered[i][j] + cells[i][j] as u32;
+            }
+        }
+
+        Some(Self { xs, ys, covered })
+    }
+
+    // Whether every point of the closed rectangle with the given opposite
+    // corners is inside the polygon or on its boundary.
+    pub fn contains_rectangle(&self, corner1: Point, corner2: Point) -> bool {
+        let compress = |coords: &[i64], value: i64| match coords.binary_search(&value) {
+            Ok(k) => Some(2 * k),
+            Err(k) if 0 < k && k < coords.len() => Some(2 * k - 1),
+            Err(_) => None,
+        };
+        let (x1, x2) = crate::min_max(corner1.x, corner2.x);
+        let (y1, y2) = crate::min_max(corner1.y, corner2.y);
+        let (Some(j1), Some(j2), Some(i1), Some(i2)) = (
+            compress(&self.xs, x1),
+            compress(&self.xs, x2),
+            compress(&self.ys, y1),
+            compress(&self.ys, y2),
+        ) else {
+            return false;
+        };
+        let total = self.covered[i2 + 1][j2 + 1] + self.covered[i1][j1]
+            - self.covered[i1][j2 + 1]
+            - self.covered[i2 + 1][j1];
+        total as usize == (i2 - i1 + 1) * (j2 - j1 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Vec<Point> {
+        points.iter().map(|p| Point::from(*p)).collect()
+    }
+
+    // An L shape, counter-clockwise:
+    //
+    //  (0,4)+--+(2,4)
+    //       |  |
+    //       |  +-----+(5,2)
+    //       |        |
+    //  (0,0)+--------+(5,0)
+    fn l_shape() -> Vec<Point> {
+        polygon(&[(0, 0), (5, 0), (5, 2), (2, 2), (2, 4), (0, 4)])
+    }
+
+    #[test]
+    fn test_area_and_lattice_points() {
+        let shape = l_shape();
+        assert_eq!(twice_signed_area(&shape), 28);
+        let reversed: Vec<Point> = shape.iter().rev().copied().collect();
+        assert_eq!(twice_signed_area(&reversed), -28);
+        assert_eq!(boundary_points(&shape), 18);
+        // Interior points: (1..=4, 1) and (1, 2..=3)
+        assert_eq!(interior_points(&shape), 6);
+
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 4)]);
+        assert_eq!(interior_points(&triangle), 3);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let shape = l_shape();
+        for x in -1..=6 {
+            for y in -1..=5 {
+                let p = Point::new(x, y);
+                let location = ray_cast(p, &shape);
+                let expected = if (0..=5).contains(&x) && (0..=2).contains(&y)
+                    || (0..=2).contains(&x) && (0..=4).contains(&y)
+                {
+                    let interior = (1..=4).contains(&x) && y == 1 || x == 1 && (1..=3).contains(&y);
+                    if interior {
+                        Location::Inside
+                    } else {
+                        Location::Boundary
+                    }
+                } else {
+                    Location::Outside
+                };
+                assert_eq!(location, expected, "{p}");
+                match location {
+                    Location::Inside => assert_eq!(winding_number(p, &shape), 1),
+                    Location::Outside => assert_eq!(winding_number(p, &shape), 0),
+                    Location::Boundary => (),
+                }
+            }
+        }
+
+        // Winding number counts repeated loops
+        let square = polygon(&[(0, 0), (2, 0), (2, 2), (0, 2)]);
+        let twice: Vec<Point> = square.iter().chain(&square).copied().collect();
+        assert_eq!(winding_number(Point::new(1, 1), &twice), 2);
+    }
+
+    #[test]
+    fn test_segments_intersect() {
+        let p = |x, y| Point::new(x, y);
+        assert!(segments_intersect(p(0, 0), p(4, 4), p(0, 4), p(4, 0)));
+        assert!(!segments_intersect(p(0, 0), p(1, 1), p(0, 4), p(4, 0)));
+        // Touching at an endpoint
+        assert!(segments_intersect(p(0, 0), p(2, 2), p(2, 2), p(3, 0)));
+        // Collinear, overlapping and disjoint
+        assert!(segments_intersect(p(0, 0), p(3, 0), p(2, 0), p(5, 0)));
+        assert!(!segments_intersect(p(0, 0), p(1, 0), p(2, 0), p(5, 0)));
+        // Parallel
+        assert!(!segments_intersect(p(0, 0), p(3, 0), p(0, 1), p(3, 1)));
+    }
+
+    #[test]
+    fn test_rectilinear_containment() {
+        let shape = RectilinearPolygon::new(&l_shape()).unwrap();
+        let p = |x, y| Point::new(x, y);
+        assert!(shape.contains_rectangle(p(0, 0), p(5, 2)));
+        assert!(shape.contains_rectangle(p(2, 4), p(0, 0)));
+        assert!(shape.contains_rectangle(p(1, 1), p(4, 1)));
+        assert!(!shape.contains_rectangle(p(0, 0), p(3, 3)));
+        assert!(!shape.contains_rectangle(p(1, 1), p(6, 1)));
+        // Degenerate rectangles are points and segments
+        assert!(shape.contains_rectangle(p(3, 2), p(3, 2)));
+        assert!(!shape.contains_rectangle(p(3, 3), p(3, 3)));
+
+        // Brute force against ray casting for every rectangle near the shape.
+        let l = l_shape();
+        for (x1, y1, x2, y2) in (-1..=6).flat_map(|a| {
+            (-1..=5)
+                .flat_map(move |b| (a..=6).flat_map(move |c| (b..=5).map(move |d| (a, b, c, d))))
+        }) {
+            let expected =
+                (x1..=x2).all(|x| (y1..=y2).all(|y| ray_cast(p(x, y), &l) != Location::Outside));
+            assert_eq!(shape.contains_rectangle(p(x1, y1), p(x2, y2)), expected);
+        }
+
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 4)]);
+        assert!(RectilinearPolygon::new(&triangle).is_none());
+    }
+}
@@ -1,10 +1,31 @@
 use std::{collections::HashMap, hash::Hash};
 
+pub mod cliques;
+pub mod corridors;
+pub mod directed;
+pub mod max_flow;
+pub mod min_cut;
+
+pub use self::directed::{Cycle, DirectedGraph};
+
+#[derive(Debug, Clone)]
 pub struct UndirectedWeightedGraph<L> {
     pub inner: HashMap<Node<L>, HashMap<Node<L>, u64>>,
 }
 
+impl<L: Copy + PartialEq + Eq + Hash> Default for UndirectedWeightedGraph<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<L: Copy + PartialEq + Eq + Hash> UndirectedWeightedGraph<L> {
+    pub fn new() -> Self {
+        Self {
+            inner: HashMap::new(),
+        }
+    }
+
     pub fn unweighted<I>(nodes: I) -> Self
     where
         I: IntoIterator<Item = (L, Vec<L>)>,
@@ -21,6 +42,19 @@ impl<L: Copy + PartialEq + Eq + Hash> UndirectedWeightedGraph<L> {
         Self { inner }
     }
 
+    // Adding an edge which already exists increases its weight.
+    pub fn add_edge(&mut self, x: L, y: L, weight: u64) {
+        let a = Node::new(x);
+        let b = Node::new(y);
+        *self
+            .inner
+            .entry(a.clone())
+            .or_default()
+            .entry(b.clone())
+            .or_default() += weight;
+        *self.inner.entry(b).or_default().entry(a).or_default() += weight;
+    }
+
     pub fn neighbors_of(&self, a: &Node<L>) -> impl Iterator<Item = &Node<L>> {
         self.inner[a].keys()
     }
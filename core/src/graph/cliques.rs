@@ -0,0 +1,304 @@
+use {
+    crate::bitset::GrowableBitSet,
+    std::{collections::HashMap, hash::Hash},
+};
+
+// Undirected graph on nodes `0..n` with adjacency stored as bitsets, so that
+// the set operations needed for clique search are cheap word-wise operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliqueGraph {
+    adjacency: Vec<GrowableBitSet>,
+}
+
+impl CliqueGraph {
+    pub fn new(n: usize) -> Self {
+        Self {
+            adjacency: vec![GrowableBitSet::with_capacity(n); n],
+        }
+    }
+
+    // Assigns ids to the labels in the order they are first seen.
+    // The returned vector maps ids back to labels.
+    pub fn labelled<L, I>(edges: I) -> (Self, Vec<L>)
+    where
+        L: Clone + Eq + Hash,
+        I: IntoIterator<Item = (L, L)>,
+    {
+        let mut index: HashMap<L, usize> = HashMap::new();
+        let mut labels = Vec::new();
+        let mut id_edges = Vec::new();
+        let mut id_of = |x: L| -> usize {
+            *index.entry(x.clone()).or_insert_with(|| {
+                labels.push(x);
+                labels.len() - 1
+            })
+        };
+        for (a, b) in edges {
+            id_edges.push((id_of(a), id_of(b)));
+        }
+
+        let mut graph = Self::new(labels.len());
+        for (a, b) in id_edges {
+            graph.add_edge(a, b);
+        }
+        (graph, labels)
+    }
+
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    // Self-loops are ignored since they are irrelevant to cliques.
+    pub fn add_edge(&mut self, a: usize, b: usize) {
+        if a != b {
+            self.adjacency[a].insert(b);
+            self.adjacency[b].insert(a);
+        }
+    }
+
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.adjacency[a].contains(b)
+    }
+
+    pub fn neighbors(&self, a: usize) -> &GrowableBitSet {
+        &self.adjacency[a]
+    }
+
+    // Order nodes by repeatedly removing the one of minimum degree.
+    // Returns the order and the degeneracy of the graph.
+    pub fn degeneracy_ordering(&self) -> (Vec<usize>, usize) {
+        let n = self.len();
+        let mut degrees: Vec<usize> = self.adjacency.iter().map(|xs| xs.len()).collect();
+        let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (a, d) in degrees.iter().enumerate() {
+            buckets[*d].push(a);
+        }
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut degeneracy = 0;
+        let mut d = 0;
+        while order.len() < n {
+            // Buckets may contain stale entries, which are skipped.
+            let Some(a) = buckets[d].pop() else {
+                d += 1;
+                continue;
+            };
+            if removed[a] || degrees[a] != d {
+                continue;
+            }
+            removed[a] = true;
+            degeneracy = degeneracy.max(d);
+            order.push(a);
+            for b in self.adjacency[a].iter() {
+                if !removed[b] {
+                    degrees[b] -= 1;
+                    buckets[degrees[b]].push(b);
+                }
+            }
+            d = d.saturating_sub(1);
+        }
+        (order, degeneracy)
+    }
+
+    // Calls `f` with every maximal clique. Uses Bron-Kerbosch with pivoting, where
+    // the outer level visits nodes in degeneracy order to keep the candidate sets small.
+    // See https://en.wikipedia.org/wiki/Bron%E2%80%93Kerbosch_algorithm
+    pub fn for_each_maximal_clique<F>(&self, mut f: F)
+    where
+        F: FnMut(&[usize]),
+    {
+        let n = self.len();
+        let (order, _) = self.degeneracy_ordering();
+        let mut p = GrowableBitSet::full(n);
+        let mut x = GrowableBitSet::with_capacity(n);
+        let mut r = Vec::new();
+        for v in order {
+            r.push(v);
+            self.bron_kerbosch(
+                &mut r,
+                &p & &self.adjacency[v],
+                &x & &self.adjacency[v],
+                &mut f,
+            );
+            r.pop();
+            p.remove(v);
+            x.insert(v);
+        }
+    }
+
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let mut result = Vec::new();
+        self.for_each_maximal_clique(|c| result.push(c.to_vec()));
+        result
+    }
+
+    fn bron_kerbosch<F>(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: GrowableBitSet,
+        mut x: GrowableBitSet,
+        f: &mut F,
+    ) where
+        F: FnMut(&[usize]),
+    {
+        if p.is_empty() {
+            if x.is_empty() {
+                f(r);
+            }
+            return;
+        }
+
+        // Choose the pivot maximizing the number of candidates it rules out.
+        let pivot = p
+            .iter()
+            .chain(x.iter())
+            .max_by_key(|u| (&p & &self.adjacency[*u]).len())
+            .expect("P is non-empty");
+        for v in (&p - &self.adjacency[pivot]).iter() {
+            r.push(v);
+            self.bron_kerbosch(r, &p & &self.adjacency[v], &x & &self.adjacency[v], f);
+            r.pop();
+            p.remove(v);
+            x.insert(v);
+        }
+    }
+
+    // Branch and bound search for a largest clique. Ties are broken arbitrarily.
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        let mut best = Vec::new();
+        let mut r = Vec::new();
+        self.expand_maximum(&mut r, GrowableBitSet::full(self.len()), &mut best);
+        best.sort();
+        best
+    }
+
+    fn expand_maximum(&self, r: &mut Vec<usize>, mut p: GrowableBitSet, best: &mut Vec<usize>) {
+        if p.is_empty() {
+            if r.len() > best.len() {
+                *best = r.clone();
+            }
+            return;
+        }
+        // Visit candidates with the most neighbors first to find large cliques early.
+        let mut candidates: Vec<usize> = p.iter().collect();
+        candidates.sort_by_key(|v| std::cmp::Reverse(self.adjacency[*v].len()));
+        for v in candidates {
+            if r.len() + p.len() <= best.len() {
+                return;
+            }
+            r.push(v);
+            self.expand_maximum(r, &p & &self.adjacency[v], best);
+            r.pop();
+            p.remove(v);
+        }
+    }
+
+    // All cliques with exactly `k` nodes, each listed once with ids in increasing order.
+    pub fn k_cliques(&self, k: usize) -> Vec<Vec<usize>> {
+        let mut result = Vec::new();
+        if k == 0 {
+            return result;
+        }
+        let mut r = Vec::with_capacity(k);
+        self.expand_k(&mut r, GrowableBitSet::full(self.len()), k, &mut result);
+        result
+    }
+
+    fn expand_k(
+        &self,
+        r: &mut Vec<usize>,
+        p: GrowableBitSet,
+        k: usize,
+        result: &mut Vec<Vec<usize>>,
+    ) {
+        if r.len() == k {
+            result.push(r.clone());
+            return;
+        }
+        for v in p.iter() {
+            // Only extend with larger ids so each clique is generated once.
+            let mut later = &p & &self.adjacency[v];
+            later.remove_up_to(v);
+            if r.len() + 1 + later.len() < k {
+                continue;
+            }
+            r.push(v);
+            self.expand_k(r, later, k, result);
+            r.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_graph() -> CliqueGraph {
+        // Two overlapping 4-cliques and a pendant triangle.
+        let mut graph = CliqueGraph::new(8);
+        for (a, b) in [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+            (2, 5),
+            (3, 5),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (5, 7),
+        ] {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_maximal_cliques() {
+        let mut cliques: Vec<Vec<usize>> = example_graph()
+            .maximal_cliques()
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect();
+        cliques.sort();
+        assert_eq!(
+            cliques,
+            vec![vec![0, 1, 2, 3], vec![2, 3, 4, 5], vec![5, 6, 7]]
+        );
+    }
+
+    #[test]
+    fn test_maximum_clique() {
+        let clique = example_graph().maximum_clique();
+        assert_eq!(clique.len(), 4);
+        assert!(clique == vec![0, 1, 2, 3] || clique == vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_k_cliques() {
+        let graph = example_graph();
+        // Each 4-clique has 4 triangles, plus the pendant triangle.
+        assert_eq!(graph.k_cliques(3).len(), 9);
+        assert_eq!(graph.k_cliques(4).len(), 2);
+        assert!(graph.k_cliques(5).is_empty());
+        assert_eq!(graph.k_cliques(1).len(), 8);
+    }
+
+    #[test]
+    fn test_degeneracy() {
+        let (order, degeneracy) = example_graph().degeneracy_ordering();
+        assert_eq!(degeneracy, 3);
+        assert_eq!(order.len(), 8);
+    }
+}
@@ -0,0 +1,246 @@
+use {
+    crate::grid::{Grid, Position},
+    std::collections::{HashMap, HashSet},
+};
+
+// A grid maze compressed into a graph of junctions connected by corridors.
+// Junctions are open cells with more than two open neighbors, plus any
+// explicitly requested points (e.g. the start and end of the maze), which
+// are always listed first in the order given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorridorGraph {
+    pub junctions: Vec<Position>,
+    // Outgoing corridors for each junction (indexed like `junctions`).
+    pub corridors: Vec<Vec<Corridor>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corridor {
+    pub to: usize,
+    // Number of steps from one junction to the other.
+    pub length: u64,
+    // Sum of the step costs along the corridor, equal to `length` unless
+    // built with `from_grid_weighted`.
+    pub cost: u64,
+    // The cell entered by the first step and the cell left by the last step,
+    // which give the directions the corridor leaves and enters its junctions.
+    pub first: Position,
+    pub last: Position,
+    // Cells strictly between the two junctions, in walking order.
+    pub cells: Vec<Position>,
+}
+
+impl CorridorGraph {
+    // `is_open` determines which cells are part of the maze, and `can_move` restricts
+    // which steps between adjacent open cells are allowed (e.g. one-way slopes). A corridor
+    // only produces an edge in the direction(s) where every step is allowed.
+    pub fn from_grid<T, F, G>(
+        grid: &Grid<T>,
+        points_of_interest: &[Position],
+        is_open: F,
+        can_move: G,
+    ) -> Self
+    where
+        F: Fn(&T) -> bool,
+        G: Fn(Position, Position) -> bool,
+    {
+        Self::from_grid_weighted(grid, points_of_interest, is_open, can_move, |_, _, _| 1)
+    }
+
+    // Like `from_grid`, but `step_cost(previous, current, next)` gives the cost of
+    // stepping from `current` to `next`, where `previous` is the cell before
+    // `current` on the corridor (`None` for the first step out of a junction).
+    // This allows e.g. charging for turns inside a corridor, while turns at the
+    // junctions are left to the caller using `Corridor::first` and `Corridor::last`.
+    pub fn from_grid_weighted<T, F, G, H>(
+        grid: &Grid<T>,
+        points_of_interest: &[Position],
+        is_open: F,
+        can_move: G,
+        step_cost: H,
+    ) -> Self
+    where
+        F: Fn(&T) -> bool,
+        G: Fn(Position, Position) -> bool,
+        H: Fn(Option<Position>, Position, Position) -> u64,
+    {
+        let nc = grid.neighbor_context();
+        let open_neighbors = |x: Position| -> Vec<Position> {
+            nc.cardinal_neighbors_of(x)
+                .filter(|y| is_open(&grid[*y]))
+                .collect()
+        };
+
+        let mut junctions: Vec<Position> = points_of_interest.to_vec();
+        junctions.extend(
+            grid.index_range()
+                .filter(|x| is_open(&grid[*x]) && open_neighbors(*x).len() > 2)
+                .filter(|x| !points_of_interest.contains(x)),
+        );
+        let index: HashMap<Position, usize> =
+            junctions.iter().enumerate().map(|(i, x)| (*x, i)).collect();
+
+        let mut corridors: Vec<Vec<Corridor>> = vec![Vec::new(); junctions.len()];
+        for (i, start) in junctions.iter().enumerate() {
+            for first in open_neighbors(*start) {
+                let mut cells = Vec::new();
+                let mut forward = can_move(*start, first);
+                let mut cost = step_cost(None, *start, first);
+                let mut prev = *start;
+                let mut current = first;
+                // Walk along the corridor until reaching another junction or a dead end.
+                let end = loop {
+                    if let Some(j) = index.get(&current) {
+                        break Some(*j);
+                    }
+                    cells.push(current);
+                    let Some(next) = open_neighbors(current).into_iter().find(|y| *y != prev)
+                    else {
+                        break None;
+                    };
+                    forward &= can_move(current, next);
+                    cost += step_cost(Some(prev), current, next);
+                    prev = current;
+                    current = next;
+                };
+                if let (true, Some(j)) = (forward, end) {
+                    // Loops back to the same junction are never part of a simple path.
+                    if j != i {
+                        corridors[i].push(Corridor {
+                            to: j,
+                            length: cells.len() as u64 + 1,
+                            cost,
+                            first,
+                            last: prev,
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+
+        Self {
+            junctions,
+            corridors,
+        }
+    }
+
+    pub fn index_of(&self, x: Position) -> Option<usize> {
+        self.junctions.iter().position(|y| *y == x)
+    }
+
+    // Length of the longest path from `start` to `end` which does not visit
+    // any junction (and hence any cell) more than once.
+    // Returns `None` if `end` is unreachable.
+    pub fn longest_path(&self, start: usize, end: usize) -> Option<u64> {
+        let mut visited = vec![false; self.junctions.len()];
+        self.longest_path_from(start, end, &mut visited)
+    }
+
+    fn longest_path_from(&self, a: usize, end: usize, visited: &mut [bool]) -> Option<u64> {
+        if a == end {
+            return Some(0);
+        }
+        visited[a] = true;
+        let mut best = None;
+        for corridor in &self.corridors[a] {
+            if visited[corridor.to] {
+                continue;
+            }
+            if let Some(length) = self.longest_path_from(corridor.to, end, visited) {
+                best = best.max(Some(length + corridor.length));
+            }
+        }
+        visited[a] = false;
+        best
+    }
+
+    // All grid cells covered by the compressed graph.
+    pub fn cells(&self) -> HashSet<Position> {
+        self.junctions
+            .iter()
+            .copied()
+            .chain(
+                self.corridors
+                    .iter()
+                    .flatten()
+                    .flat_map(|c| c.cells.iter().copied()),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{basic_grid, grid::difference},
+    };
+
+    const MAZE: &str = "\
+#.#####
+#.....#
+#.###.#
+#.....#
+###.###
+###...#
+#####.#";
+
+    #[test]
+    fn test_compress() {
+        let grid = basic_grid(MAZE);
+        let graph = CorridorGraph::from_grid(&grid, &[(0, 1), (6, 5)], |b| *b != b'#', |_, _| true);
+        // Start, end and the two junctions at the top-left and bottom-middle of the loop.
+        assert_eq!(graph.junctions.len(), 4);
+        let start = graph.index_of((0, 1)).unwrap();
+        let end = graph.index_of((6, 5)).unwrap();
+        assert_eq!(graph.longest_path(start, end), Some(14));
+        assert_eq!(graph.longest_path(end, start), Some(14));
+        assert_eq!(graph.cells().len(), 18);
+    }
+
+    #[test]
+    fn test_one_way() {
+        let grid = basic_grid(MAZE);
+        // Forbid moving left, so only the short side of the loop can be used.
+        let graph =
+            CorridorGraph::from_grid(&grid, &[(0, 1), (6, 5)], |b| *b != b'#', |x, y| y.1 >= x.1);
+        let start = graph.index_of((0, 1)).unwrap();
+        let end = graph.index_of((6, 5)).unwrap();
+        assert_eq!(graph.longest_path(start, end), Some(10));
+        assert_eq!(graph.longest_path(end, start), None);
+    }
+
+    #[test]
+    fn test_turn_costs() {
+        let grid = basic_grid(MAZE);
+        let turn_cost = |prev: Option<Position>, x: Position, y: Position| match prev {
+            Some(prev) if difference(prev, x) != difference(x, y) => 11,
+            _ => 1,
+        };
+        let graph = CorridorGraph::from_grid_weighted(
+            &grid,
+            &[(0, 1), (6, 5)],
+            |b| *b != b'#',
+            |_, _| true,
+            turn_cost,
+        );
+        let a = graph.index_of((1, 1)).unwrap();
+        let b = graph.index_of((3, 3)).unwrap();
+        let mut loop_sides: Vec<(u64, u64, Position, Position)> = graph.corridors[a]
+            .iter()
+            .filter(|c| c.to == b)
+            .map(|c| (c.length, c.cost, c.first, c.last))
+            .collect();
+        loop_sides.sort();
+        // The left side turns once, the right side twice.
+        assert_eq!(
+            loop_sides,
+            vec![(4, 14, (2, 1), (3, 2)), (8, 28, (1, 2), (3, 4))]
+        );
+
+        // Without weights every step costs one.
+        let graph = CorridorGraph::from_grid(&grid, &[(0, 1), (6, 5)], |b| *b != b'#', |_, _| true);
+        assert!(graph.corridors.iter().flatten().all(|c| c.cost == c.length));
+    }
+}
@@ -0,0 +1,444 @@
+use {
+    crate::intern::{Id, Interner},
+    std::{
+        collections::{BTreeMap, HashMap, HashSet, VecDeque},
+        hash::Hash,
+    },
+};
+
+// Directed graph with labelled nodes. Internally nodes are identified by
+// dense indices (in insertion order) so the algorithms can use flat vectors
+// instead of hashing labels.
+#[derive(Debug, Clone)]
+pub struct DirectedGraph<L> {
+    labels: Interner<L>,
+    successors: Vec<BTreeMap<usize, u64>>,
+}
+
+// Returned when an algorithm requiring a DAG encounters a cycle.
+// The nodes are listed in the order the edges are followed, the last
+// node has an edge back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<L> {
+    pub nodes: Vec<L>,
+}
+
+impl<L> Default for DirectedGraph<L> {
+    fn default() -> Self {
+        Self {
+            labels: Interner::default(),
+            successors: Vec::new(),
+        }
+    }
+}
+
+impl<L: Clone + Eq + Hash> DirectedGraph<L> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Construct a graph with unit weight edges from an adjacency list.
+    pub fn unweighted<I, J>(nodes: I) -> Self
+    where
+        I: IntoIterator<Item = (L, J)>,
+        J: IntoIterator<Item = L>,
+    {
+        let mut graph = Self::new();
+        for (x, neighbors) in nodes {
+            graph.add_node(x.clone());
+            for y in neighbors {
+                graph.add_edge(x.clone(), y);
+            }
+        }
+        graph
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    // Returns the id of the node, adding it to the graph if it is not already present.
+    pub fn add_node(&mut self, label: L) -> usize {
+        let id = self.labels.intern(label).index();
+        if id == self.successors.len() {
+            self.successors.push(BTreeMap::new());
+        }
+        id
+    }
+
+    pub fn add_edge(&mut self, a: L, b: L) {
+        self.add_weighted_edge(a, b, 1);
+    }
+
+    // Adding an edge which already exists increases its weight.
+    pub fn add_weighted_edge(&mut self, a: L, b: L, weight: u64) {
+        let a = self.add_node(a);
+        let b = self.add_node(b);
+        *self.successors[a].entry(b).or_default() += weight;
+    }
+
+    pub fn id_of(&self, label: &L) -> Option<usize> {
+        self.labels.get(label).map(Id::index)
+    }
+
+    pub fn label(&self, id: usize) -> &L {
+        self.labels.label(Id::new(id))
+    }
+
+    pub fn labels(&self) -> &[L] {
+        self.labels.labels()
+    }
+
+    pub fn successors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.successors[id].keys().copied()
+    }
+
+    pub fn weighted_successors(&self, id: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.successors[id].iter().map(|(b, w)| (*b, *w))
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, u64)> + '_ {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(|(a, xs)| xs.iter().map(move |(b, w)| (a, *b, *w)))
+    }
+
+    pub fn weight(&self, a: usize, b: usize) -> u64 {
+        self.successors[a].get(&b).copied().unwrap_or_default()
+    }
+
+    // The same graph with all the edges pointing the other way.
+    // Node ids are preserved.
+    pub fn reversed(&self) -> Self {
+        let mut successors = vec![BTreeMap::new(); self.len()];
+        for (a, b, w) in self.edges() {
+            successors[b].insert(a, w);
+        }
+        Self {
+            labels: self.labels.clone(),
+            successors,
+        }
+    }
+
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut result = vec![0; self.len()];
+        for (_, b, _) in self.edges() {
+            result[b] += 1;
+        }
+        result
+    }
+
+    // Kahn's algorithm. Ties are broken by node id, so the order is deterministic.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, Cycle<L>> {
+        self.topological_sort_within(&vec![true; self.len()])
+    }
+
+    // Topological order of just the nodes reachable from `start`, so cycles
+    // elsewhere in the graph do not matter.
+    pub fn topological_sort_from(&self, start: usize) -> Result<Vec<usize>, Cycle<L>> {
+        let mut members = vec![false; self.len()];
+        for x in self.reachable_from(start) {
+            members[x] = true;
+        }
+        self.topological_sort_within(&members)
+    }
+
+    // Kahn's algorithm on the subgraph of the nodes `x` with `members[x]`.
+    fn topological_sort_within(&self, members: &[bool]) -> Result<Vec<usize>, Cycle<L>> {
+        let mut in_degrees = vec![0; self.len()];
+        for (a, b, _) in self.edges() {
+            if members[a] && members[b] {
+                in_degrees[b] += 1;
+            }
+        }
+        let mut queue: VecDeque<usize> = (0..self.len())
+            .filter(|x| members[*x] && in_degrees[*x] == 0)
+            .collect();
+        let n = members.iter().filter(|m| **m).count();
+        let mut result = Vec::with_capacity(n);
+        while let Some(a) = queue.pop_front() {
+            result.push(a);
+            for b in self.successors(a).filter(|b| members[*b]) {
+                in_degrees[b] -= 1;
+                if in_degrees[b] == 0 {
+                    queue.push_back(b);
+                }
+            }
+        }
+
+        if result.len() == n {
+            Ok(result)
+        } else {
+            Err(self.find_cycle(&in_degrees))
+        }
+    }
+
+    pub fn topological_labels(&self) -> Result<Vec<L>, Cycle<L>> {
+        let order = self.topological_sort()?;
+        Ok(order.into_iter().map(|x| self.label(x).clone()).collect())
+    }
+
+    // Every node remaining after Kahn's algorithm has a predecessor which also
+    // remains (nodes outside the sorted subgraph have no remaining in-degree),
+    // so walking backwards must eventually repeat a node.
+    fn find_cycle(&self, remaining_in_degrees: &[usize]) -> Cycle<L> {
+        let reversed = self.reversed();
+        let start = (0..self.len())
+            .find(|x| remaining_in_degrees[*x] > 0)
+            .expect("Some node is left over");
+
+        let mut position: HashMap<usize, usize> = HashMap::new();
+        let mut path = Vec::new();
+        let mut current = start;
+        while !position.contains_key(&current) {
+            position.insert(current, path.len());
+            path.push(current);
+            current = reversed
+                .successors(current)
+                .find(|x| remaining_in_degrees[*x] > 0)
+                .expect("Remaining node has remaining predecessor");
+        }
+
+        // The path was built following edges backwards, so reverse it.
+        let mut nodes: Vec<L> = path[position[&current]..]
+            .iter()
+            .map(|x| self.label(*x).clone())
+            .collect();
+        nodes.reverse();
+        Cycle { nodes }
+    }
+
+    // Tarjan's algorithm, implemented iteratively to avoid stack overflows
+    // on large graphs. Components are returned in reverse topological order
+    // (i.e. a component is listed before any component that can reach it).
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let n = self.len();
+        let mut index = vec![UNVISITED; n];
+        let mut low_link = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            let mut call_stack: Vec<(usize, Vec<usize>)> = Vec::new();
+            index[root] = counter;
+            low_link[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            call_stack.push((root, self.successors(root).collect()));
+
+            while let Some((a, remaining)) = call_stack.last_mut() {
+                let a = *a;
+                if let Some(b) = remaining.pop() {
+                    if index[b] == UNVISITED {
+                        index[b] = counter;
+                        low_link[b] = counter;
+                        counter += 1;
+                        stack.push(b);
+                        on_stack[b] = true;
+                        call_stack.push((b, self.successors(b).collect()));
+                    } else if on_stack[b] {
+                        low_link[a] = low_link[a].min(index[b]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some((parent, _)) = call_stack.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[a]);
+                }
+                if low_link[a] == index[a] {
+                    let mut component = Vec::new();
+                    loop {
+                        let x = stack.pop().expect("Node is on the stack");
+                        on_stack[x] = false;
+                        component.push(x);
+                        if x == a {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    pub fn reachable_from(&self, start: usize) -> HashSet<usize> {
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        let mut stack = vec![start];
+        while let Some(a) = stack.pop() {
+            for b in self.successors(a) {
+                if visited.insert(b) {
+                    stack.push(b);
+                }
+            }
+        }
+        visited
+    }
+
+    pub fn count_paths(&self, from: usize, to: usize) -> Result<u64, Cycle<L>> {
+        self.count_paths_through(from, to, &[])
+    }
+
+    // Count the paths from `from` to `to` which visit all the nodes in `via`
+    // (in any order). The part of the graph reachable from `from` must be acyclic.
+    // At most 64 `via` nodes are supported.
+    pub fn count_paths_through(
+        &self,
+        from: usize,
+        to: usize,
+        via: &[usize],
+    ) -> Result<u64, Cycle<L>> {
+        assert!(via.len() <= 64, "Too many nodes to pass through");
+        let order = self.topological_sort_from(from)?;
+
+        let mask_of = |x: usize| -> u64 {
+            via.iter()
+                .enumerate()
+                .filter(|(_, v)| **v == x)
+                .fold(0, |acc, (i, _)| acc | (1 << i))
+        };
+        let full_mask = if via.len() == 64 {
+            u64::MAX
+        } else {
+            (1 << via.len()) - 1
+        };
+
+        // counts[x] maps a set of visited `via` nodes to the number of paths
+        // from `from` to `x` which visited exactly those nodes.
+        let mut counts: Vec<HashMap<u64, u64>> = vec![HashMap::new(); self.len()];
+        counts[from].insert(mask_of(from), 1);
+        for a in order {
+            if counts[a].is_empty() || a == to {
+                continue;
+            }
+            let current = std::mem::take(&mut counts[a]);
+            for b in self.successors(a) {
+                let b_mask = mask_of(b);
+                for (mask, count) in current.iter() {
+                    *counts[b].entry(mask | b_mask).or_default() += count;
+                }
+            }
+            counts[a] = current;
+        }
+
+        Ok(counts[to].get(&full_mask).copied().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = DirectedGraph::unweighted([
+            ("shirt", vec!["tie", "belt"]),
+            ("tie", vec!["jacket"]),
+            ("pants", vec!["shoes", "belt"]),
+            ("belt", vec!["jacket"]),
+            ("socks", vec!["shoes"]),
+        ]);
+        let order = graph.topological_labels().unwrap();
+        assert_eq!(order.len(), graph.len());
+        let position = |x: &str| order.iter().position(|y| *y == x).unwrap();
+        for (a, b, _) in graph.edges() {
+            assert!(position(graph.label(a)) < position(graph.label(b)));
+        }
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let graph =
+            DirectedGraph::unweighted([(0, vec![1]), (1, vec![2]), (2, vec![3]), (3, vec![1])]);
+        let cycle = graph.topological_sort().unwrap_err();
+        assert_eq!(cycle.nodes.len(), 3);
+        for (i, a) in cycle.nodes.iter().enumerate() {
+            let b = cycle.nodes[(i + 1) % cycle.nodes.len()];
+            let (a, b) = (graph.id_of(a).unwrap(), graph.id_of(&b).unwrap());
+            assert_eq!(graph.weight(a, b), 1);
+        }
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = DirectedGraph::unweighted([
+            ('a', vec!['b']),
+            ('b', vec!['c', 'e', 'f']),
+            ('c', vec!['d', 'g']),
+            ('d', vec!['c', 'h']),
+            ('e', vec!['a', 'f']),
+            ('f', vec!['g']),
+            ('g', vec!['f']),
+            ('h', vec!['d', 'g']),
+        ]);
+        let mut components: Vec<Vec<char>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|c| {
+                let mut labels: Vec<char> = c.into_iter().map(|x| *graph.label(x)).collect();
+                labels.sort();
+                labels
+            })
+            .collect();
+        assert_eq!(components[0], vec!['f', 'g']);
+        components.sort();
+        assert_eq!(
+            components,
+            vec![vec!['a', 'b', 'e'], vec!['c', 'd', 'h'], vec!['f', 'g']]
+        );
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = DirectedGraph::unweighted([
+            ("svr", vec!["aaa", "bbb"]),
+            ("aaa", vec!["fft"]),
+            ("fft", vec!["ccc"]),
+            ("bbb", vec!["tty"]),
+            ("tty", vec!["ccc"]),
+            ("ccc", vec!["ddd", "eee"]),
+            ("ddd", vec!["hub"]),
+            ("hub", vec!["fff"]),
+            ("eee", vec!["dac"]),
+            ("dac", vec!["fff"]),
+            ("fff", vec!["ggg", "hhh"]),
+            ("ggg", vec!["out"]),
+            ("hhh", vec!["out"]),
+            // A cycle which cannot be reached from `svr`.
+            ("xxx", vec!["yyy"]),
+            ("yyy", vec!["xxx", "out"]),
+        ]);
+        let id = |x| graph.id_of(&x).unwrap();
+        assert_eq!(graph.count_paths(id("svr"), id("out")), Ok(8));
+        assert!(graph.count_paths(id("xxx"), id("out")).is_err());
+        assert_eq!(
+            graph.count_paths_through(id("svr"), id("out"), &[id("dac"), id("fft")]),
+            Ok(2)
+        );
+        assert_eq!(graph.count_paths(id("out"), id("svr")), Ok(0));
+
+        let reachable = graph.reversed().reachable_from(id("dac"));
+        let mut labels: Vec<&str> = reachable.into_iter().map(|x| *graph.label(x)).collect();
+        labels.sort();
+        assert_eq!(
+            labels,
+            vec!["aaa", "bbb", "ccc", "dac", "eee", "fft", "svr", "tty"]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use {
+    super::{DirectedGraph, Node, UndirectedWeightedGraph},
+    std::{collections::VecDeque, hash::Hash},
+};
+
+#[derive(Debug, Clone)]
+pub struct MaxFlow<L> {
+    pub value: u64,
+    // Edges with remaining capacity after the flow is pushed (including the
+    // reverse edges which would allow flow to be undone). Node ids match the input graph.
+    pub residual: DirectedGraph<L>,
+    // Nodes reachable from the source in the residual graph.
+    pub source_side: Vec<L>,
+    // Edges of the input graph from the source side to the sink side.
+    // Their total capacity is equal to the flow value.
+    pub cut_edges: Vec<(L, L, u64)>,
+}
+
+impl<L: Copy + Eq + Hash> From<&UndirectedWeightedGraph<L>> for DirectedGraph<Node<L>> {
+    // Each undirected edge becomes a pair of directed edges with the same weight.
+    fn from(value: &UndirectedWeightedGraph<L>) -> Self {
+        let mut graph = DirectedGraph::new();
+        for (a, neighbors) in &value.inner {
+            graph.add_node(a.clone());
+            for (b, weight) in neighbors {
+                graph.add_weighted_edge(a.clone(), b.clone(), *weight);
+            }
+        }
+        graph
+    }
+}
+
+// Maximum flow from `source` to `sink` using edge weights as capacities.
+// See https://en.wikipedia.org/wiki/Dinic%27s_algorithm
+pub fn max_flow<L>(graph: &DirectedGraph<L>, source: usize, sink: usize) -> MaxFlow<L>
+where
+    L: Clone + Eq + Hash,
+{
+    let mut network = FlowNetwork::new(graph);
+    let value = network.dinic(source, sink);
+
+    let mut residual = DirectedGraph::new();
+    for label in graph.labels() {
+        residual.add_node(label.clone());
+    }
+    for edge in &network.edges {
+        if edge.capacity > 0 {
+            let (a, b) = (graph.label(edge.from), graph.label(edge.to));
+            residual.add_weighted_edge(a.clone(), b.clone(), edge.capacity);
+        }
+    }
+
+    let reachable = residual.reachable_from(source);
+    let mut source_side: Vec<usize> = reachable.iter().copied().collect();
+    source_side.sort();
+    let cut_edges = graph
+        .edges()
+        .filter(|(a, b, _)| reachable.contains(a) && !reachable.contains(b))
+        .map(|(a, b, w)| (graph.label(a).clone(), graph.label(b).clone(), w))
+        .collect();
+
+    MaxFlow {
+        value,
+        residual,
+        source_side: source_side
+            .into_iter()
+            .map(|x| graph.label(x).clone())
+            .collect(),
+        cut_edges,
+    }
+}
+
+// Convenience wrapper for undirected graphs, where each edge can carry
+// flow in either direction up to its weight.
+pub fn max_flow_undirected<L>(
+    graph: &UndirectedWeightedGraph<L>,
+    source: &Node<L>,
+    sink: &Node<L>,
+) -> MaxFlow<Node<L>>
+where
+    L: Copy + Eq + Hash,
+{
+    let directed: DirectedGraph<Node<L>> = graph.into();
+    let source = directed.id_of(source).expect("Source in graph");
+    let sink = directed.id_of(sink).expect("Sink in graph");
+    max_flow(&directed, source, sink)
+}
+
+#[derive(Debug, Clone)]
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    capacity: u64,
+}
+
+// Edges are stored in pairs so that `i ^ 1` is the reverse of edge `i`.
+#[derive(Debug, Clone)]
+struct FlowNetwork {
+    edges: Vec<FlowEdge>,
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    fn new<L: Clone + Eq + Hash>(graph: &DirectedGraph<L>) -> Self {
+        let mut network = Self {
+            edges: Vec::new(),
+            outgoing: vec![Vec::new(); graph.len()],
+        };
+        for (a, b, capacity) in graph.edges() {
+            network.add_edge(a, b, capacity);
+        }
+        network
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, capacity: u64) {
+        self.outgoing[from].push(self.edges.len());
+        self.edges.push(FlowEdge { from, to, capacity });
+        self.outgoing[to].push(self.edges.len());
+        self.edges.push(FlowEdge {
+            from: to,
+            to: from,
+            capacity: 0,
+        });
+    }
+
+    fn dinic(&mut self, source: usize, sink: usize) -> u64 {
+        if source == sink {
+            return 0;
+        }
+        let mut total = 0;
+        while let Some(levels) = self.levels(source, sink) {
+            let mut next_edge = vec![0; self.outgoing.len()];
+            loop {
+                let pushed = self.augment(source, sink, &levels, &mut next_edge);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+            }
+        }
+        total
+    }
+
+    // Breadth first search distances from the source along edges with capacity.
+    // Returns `None` if the sink is unreachable.
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut levels = vec![usize::MAX; self.outgoing.len()];
+        levels[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(a) = queue.pop_front() {
+            for e in &self.outgoing[a] {
+                let edge = &self.edges[*e];
+                if edge.capacity > 0 && levels[edge.to] == usize::MAX {
+                    levels[edge.to] = levels[a] + 1;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        (levels[sink] != usize::MAX).then_some(levels)
+    }
+
+    // Find a path in the level graph and push as much flow along it as possible.
+    // The path is kept on an explicit stack of edges so long paths cannot
+    // overflow the call stack.
+    fn augment(
+        &mut self,
+        source: usize,
+        sink: usize,
+        levels: &[usize],
+        next_edge: &mut [usize],
+    ) -> u64 {
+        let mut path: Vec<usize> = Vec::new();
+        loop {
+            let a = path.last().map_or(source, |e| self.edges[*e].to);
+            if a == sink {
+                let pushed = path
+                    .iter()
+                    .map(|e| self.edges[*e].capacity)
+                    .min()
+                    .expect("Source is not the sink");
+                for e in path {
+                    self.edges[e].capacity -= pushed;
+                    self.edges[e ^ 1].capacity += pushed;
+                }
+                return pushed;
+            }
+
+            let admissible = self.outgoing[a][next_edge[a]..].iter().position(|e| {
+                let FlowEdge { to, capacity, .. } = self.edges[*e];
+                capacity > 0 && levels[to] == levels[a] + 1
+            });
+            match admissible {
+                Some(offset) => {
+                    next_edge[a] += offset;
+                    path.push(self.outgoing[a][next_edge[a]]);
+                }
+                // Dead end, so never try it again in this phase.
+                None => {
+                    next_edge[a] = self.outgoing[a].len();
+                    let Some(e) = path.pop() else {
+                        return 0;
+                    };
+                    next_edge[self.edges[e].from] += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::random::XorShift};
+
+    #[test]
+    fn test_max_flow() {
+        let mut graph = DirectedGraph::new();
+        for (a, b, w) in [
+            ('s', 'a', 10),
+            ('s', 'c', 10),
+            ('a', 'b', 4),
+            ('a', 'c', 2),
+            ('a', 'd', 8),
+            ('c', 'd', 9),
+            ('b', 't', 10),
+            ('d', 'b', 6),
+            ('d', 't', 10),
+        ] {
+            graph.add_weighted_edge(a, b, w);
+        }
+        let id = |x| graph.id_of(&x).unwrap();
+        let flow = max_flow(&graph, id('s'), id('t'));
+        assert_eq!(flow.value, 19);
+        assert_eq!(flow.cut_edges.iter().map(|e| e.2).sum::<u64>(), 19);
+        assert!(!flow.source_side.contains(&'t'));
+        // The source can only send 1 more unit to `c`, and no more to `a`.
+        assert_eq!(flow.residual.weight(id('s'), id('a')), 0);
+        assert_eq!(flow.residual.weight(id('s'), id('c')), 1);
+    }
+
+    #[test]
+    fn test_max_flow_undirected() {
+        let mut graph = UndirectedWeightedGraph::new();
+        for (a, b) in [(1, 2), (1, 3), (2, 4), (3, 4), (2, 3)] {
+            graph.add_edge(a, b, 1);
+        }
+        let flow = max_flow_undirected(&graph, &Node::new(1), &Node::new(4));
+        assert_eq!(flow.value, 2);
+        assert_eq!(flow.cut_edges.len(), 2);
+    }
+
+    // Max flow must be equal to the minimum capacity over all s-t cuts,
+    // which can be found by brute force on small graphs.
+    #[test]
+    fn test_max_flow_min_cut() {
+        let mut rng = XorShift::new(28);
+        for _ in 0..50 {
+            let n = 2 + rng.below(7) as usize;
+            let mut graph = DirectedGraph::new();
+            for a in 0..n {
+                graph.add_node(a);
+            }
+            for a in 0..n {
+                for b in 0..n {
+                    if a != b && rng.chance(2, 5) {
+                        graph.add_weighted_edge(a, b, rng.below(10));
+                    }
+                }
+            }
+
+            let (source, sink) = (0, n - 1);
+            let min_cut = (0_u32..(1 << n))
+                .filter(|mask| mask & (1 << source) != 0 && mask & (1 << sink) == 0)
+                .map(|mask| {
+                    graph
+                        .edges()
+                        .filter(|(a, b, _)| mask & (1 << a) != 0 && mask & (1 << b) == 0)
+                        .map(|(_, _, w)| w)
+                        .sum::<u64>()
+                })
+                .min()
+                .unwrap();
+
+            let flow = max_flow(&graph, source, sink);
+            assert_eq!(flow.value, min_cut);
+            assert_eq!(flow.cut_edges.iter().map(|e| e.2).sum::<u64>(), min_cut);
+        }
+    }
+
+    #[test]
+    fn test_max_flow_long_path() {
+        // Deep enough to overflow the stack with a recursive search.
+        let n = 200_000;
+        let mut graph = DirectedGraph::new();
+        for a in 0..n {
+            graph.add_weighted_edge(a, a + 1, 1 + (a as u64 % 7));
+        }
+        let flow = max_flow(&graph, 0, n);
+        assert_eq!(flow.value, 1);
+        assert_eq!(flow.cut_edges.len(), 1);
+    }
+}
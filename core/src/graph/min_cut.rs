@@ -0,0 +1,398 @@
+use {
+    super::{Node, UndirectedWeightedGraph},
+    crate::random::XorShift,
+    std::{
+        collections::{BinaryHeap, HashMap},
+        hash::Hash,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut<L> {
+    pub weight: u64,
+    // The labels of the nodes on either side of the cut.
+    pub partitions: [Vec<L>; 2],
+    // Edges of the original graph which cross the cut, by the labels of their
+    // ends. The first label of each edge is in the first partition. Nodes
+    // merged with `merge_nodes` are named by their first label.
+    pub cut_edges: Vec<(L, L, u64)>,
+}
+
+// Global minimum cut of a weighted undirected graph.
+// Returns `None` if the graph has fewer than two nodes.
+// See https://en.wikipedia.org/wiki/Stoer%E2%80%93Wagner_algorithm
+pub fn stoer_wagner<L>(graph: &UndirectedWeightedGraph<L>) -> Option<MinCut<L>>
+where
+    L: Copy + Eq + Hash,
+{
+    stoer_wagner_until(graph, |_| false)
+}
+
+// Like `stoer_wagner`, but returns the first cut found whose weight satisfies
+// `good_enough`, e.g. when the weight of the minimum cut is known in advance.
+// Falls back to the minimum cut if no such cut is found.
+pub fn stoer_wagner_until<L, F>(
+    graph: &UndirectedWeightedGraph<L>,
+    good_enough: F,
+) -> Option<MinCut<L>>
+where
+    L: Copy + Eq + Hash,
+    F: Fn(u64) -> bool,
+{
+    let (nodes, mut contracted) = Contracted::new(graph);
+    if nodes.len() < 2 {
+        return None;
+    }
+
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    while contracted.alive.len() > 1 {
+        let (second_last, last, weight) = contracted.stoer_wagner_phase();
+        if best.as_ref().map(|(w, _)| weight < *w).unwrap_or(true) {
+            best = Some((weight, contracted.members[last].clone()));
+        }
+        if good_enough(weight) {
+            break;
+        }
+        contracted.merge(second_last, last);
+    }
+
+    let (weight, severed) = best?;
+    Some(MinCut::new(graph, &nodes, weight, &severed))
+}
+
+// Randomized global minimum cut by recursive contraction. Each trial finds
+// the minimum cut with probability `Ω(1 / log n)`, so enough trials are run that
+// failure is very unlikely. Intended for cross-checking `stoer_wagner`.
+// See https://en.wikipedia.org/wiki/Karger%27s_algorithm#Karger%E2%80%93Stein_algorithm
+pub fn karger_stein<L>(graph: &UndirectedWeightedGraph<L>, seed: u64) -> Option<MinCut<L>>
+where
+    L: Copy + Eq + Hash,
+{
+    let (nodes, contracted) = Contracted::new(graph);
+    let n = nodes.len();
+    if n < 2 {
+        return None;
+    }
+
+    let mut rng = XorShift::new(seed);
+    let log_n = (n.ilog2() + 1) as usize;
+    let (weight, severed) = (0..(log_n * log_n + 1))
+        .map(|_| contracted.karger_stein(&mut rng))
+        .min_by_key(|(weight, _)| *weight)?;
+    Some(MinCut::new(graph, &nodes, weight, &severed))
+}
+
+impl<L: Copy + Eq + Hash> MinCut<L> {
+    fn new(
+        graph: &UndirectedWeightedGraph<L>,
+        nodes: &[Node<L>],
+        weight: u64,
+        severed: &[usize],
+    ) -> Self {
+        let mut is_severed = vec![false; nodes.len()];
+        for i in severed {
+            is_severed[*i] = true;
+        }
+
+        let mut partitions = [Vec::new(), Vec::new()];
+        let mut cut_edges = Vec::new();
+        let index: HashMap<&Node<L>, usize> =
+            nodes.iter().enumerate().map(|(i, a)| (a, i)).collect();
+        for (i, a) in nodes.iter().enumerate() {
+            if is_severed[i] {
+                partitions[1].extend_from_slice(&a.name);
+                continue;
+            }
+            partitions[0].extend_from_slice(&a.name);
+            for (b, weight) in &graph.inner[a] {
+                if is_severed[index[b]] {
+                    cut_edges.push((a.name[0], b.name[0], *weight));
+                }
+            }
+        }
+
+        Self {
+            weight,
+            partitions,
+            cut_edges,
+        }
+    }
+}
+
+// Index-based copy of a graph where nodes can be contracted together.
+// Each surviving node keeps track of which original nodes were merged into it.
+#[derive(Debug, Clone)]
+struct Contracted {
+    members: Vec<Vec<usize>>,
+    adjacency: Vec<HashMap<usize, u64>>,
+    alive: Vec<usize>,
+}
+
+impl Contracted {
+    fn new<L: Copy + Eq + Hash>(graph: &UndirectedWeightedGraph<L>) -> (Vec<Node<L>>, Self) {
+        let nodes: Vec<Node<L>> = graph.inner.keys().cloned().collect();
+        let index: HashMap<&Node<L>, usize> =
+            nodes.iter().enumerate().map(|(i, a)| (a, i)).collect();
+        let adjacency = nodes
+            .iter()
+            .map(|a| {
+                graph.inner[a]
+                    .iter()
+                    .map(|(b, weight)| (index[b], *weight))
+                    .collect()
+            })
+            .collect();
+        let contracted = Self {
+            members: (0..nodes.len()).map(|i| vec![i]).collect(),
+            adjacency,
+            alive: (0..nodes.len()).collect(),
+        };
+        (nodes, contracted)
+    }
+
+    // Merge node `b` into node `a`.
+    fn merge(&mut self, a: usize, b: usize) {
+        let b_members = std::mem::take(&mut self.members[b]);
+        self.members[a].extend(b_members);
+
+        let b_neighbors = std::mem::take(&mut self.adjacency[b]);
+        for (n, weight) in b_neighbors {
+            self.adjacency[n].remove(&b);
+            if n == a {
+                continue;
+            }
+            *self.adjacency[a].entry(n).or_default() += weight;
+            *self.adjacency[n].entry(a).or_default() += weight;
+        }
+        self.alive.retain(|x| *x != b);
+    }
+
+    // Add nodes in order of how tightly connected they are to the nodes already added.
+    // Returns the last two nodes added and the weight of the cut separating the last node
+    // from the rest of the graph.
+    fn stoer_wagner_phase(&self) -> (usize, usize, u64) {
+        let n = self.adjacency.len();
+        let mut added = vec![false; n];
+        let mut connectivity = vec![0; n];
+        // Disconnected nodes are in the heap from the start so they are eventually added.
+        let mut heap: BinaryHeap<(u64, usize)> = self.alive.iter().map(|a| (0, *a)).collect();
+
+        let mut second_last = self.alive[0];
+        let mut last = self.alive[0];
+        let mut last_weight = 0;
+        while let Some((weight, a)) = heap.pop() {
+            if added[a] || weight != connectivity[a] {
+                continue;
+            }
+            added[a] = true;
+            second_last = last;
+            last = a;
+            last_weight = weight;
+            for (b, w) in &self.adjacency[a] {
+                if !added[*b] {
+                    connectivity[*b] += w;
+                    heap.push((connectivity[*b], *b));
+                }
+            }
+        }
+
+        (second_last, last, last_weight)
+    }
+
+    fn cut_weight(&self, a: usize) -> u64 {
+        self.adjacency[a].values().sum()
+    }
+
+    // Randomly contract edges (chosen proportional to weight) until `t` nodes remain.
+    fn contract_to(&mut self, t: usize, rng: &mut XorShift) {
+        while self.alive.len() > t {
+            let total: u64 = self.alive.iter().map(|a| self.cut_weight(*a)).sum();
+            if total == 0 {
+                // No edges left, so any partition has weight zero.
+                let n = self.alive.len();
+                self.merge(self.alive[n - 2], self.alive[n - 1]);
+                continue;
+            }
+
+            // Each edge is counted from both ends, which does not change the distribution.
+            let mut target = rng.below(total);
+            let (a, b) = self
+                .alive
+                .iter()
+                .flat_map(|a| self.adjacency[*a].iter().map(move |(b, w)| (*a, *b, *w)))
+                .find_map(|(a, b, w)| {
+                    if target < w {
+                        Some((a, b))
+                    } else {
+                        target -= w;
+                        None
+                    }
+                })
+                .expect("Target is less than the total");
+            self.merge(a, b);
+        }
+    }
+
+    fn karger_stein(&self, rng: &mut XorShift) -> (u64, Vec<usize>) {
+        let n = self.alive.len();
+        if n <= 6 {
+            return self.brute_force_cut();
+        }
+
+        let t = 1 + (n as f64 / std::f64::consts::SQRT_2).ceil() as usize;
+        (0..2)
+            .map(|_| {
+                let mut smaller = self.clone();
+                smaller.contract_to(t, rng);
+                smaller.karger_stein(rng)
+            })
+            .min_by_key(|(weight, _)| *weight)
+            .expect("Two trials")
+    }
+
+    // Try every bipartition of the remaining nodes.
+    fn brute_force_cut(&self) -> (u64, Vec<usize>) {
+        let n = self.alive.len();
+        let mut best = (u64::MAX, 0);
+        // The last node is always on the non-severed side to avoid counting partitions twice.
+        for mask in 1_u32..(1 << (n - 1)) {
+            let in_set = |a: usize| -> bool {
+                self.alive
+                    .iter()
+                    .position(|x| *x == a)
+                    .map(|i| mask & (1 << i) != 0)
+                    .unwrap_or(false)
+            };
+            let weight: u64 = (0..n)
+                .filter(|i| mask & (1 << i) != 0)
+                .flat_map(|i| self.adjacency[self.alive[i]].iter())
+                .filter(|(b, _)| !in_set(**b))
+                .map(|(_, w)| *w)
+                .sum();
+            if weight < best.0 {
+                best = (weight, mask);
+            }
+        }
+
+        let severed = (0..n)
+            .filter(|i| best.1 & (1 << i) != 0)
+            .flat_map(|i| self.members[self.alive[i]].iter().copied())
+            .collect();
+        (best.0, severed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from the Stoer-Wagner paper, the minimum cut has weight 4.
+    fn example_graph() -> UndirectedWeightedGraph<u8> {
+        let mut graph = UndirectedWeightedGraph::new();
+        for (a, b, w) in [
+            (1, 2, 2),
+            (1, 5, 3),
+            (2, 3, 3),
+            (2, 5, 2),
+            (2, 6, 2),
+            (3, 4, 4),
+            (3, 7, 2),
+            (4, 7, 2),
+            (4, 8, 2),
+            (5, 6, 3),
+            (6, 7, 1),
+            (7, 8, 3),
+        ] {
+            graph.add_edge(a, b, w);
+        }
+        graph
+    }
+
+    fn sorted_partitions(cut: &MinCut<u8>) -> [Vec<u8>; 2] {
+        let mut partitions = cut.partitions.clone();
+        for p in partitions.iter_mut() {
+            p.sort();
+        }
+        partitions.sort();
+        partitions
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        let cut = stoer_wagner(&example_graph()).unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(
+            sorted_partitions(&cut),
+            [vec![1, 2, 5, 6], vec![3, 4, 7, 8]]
+        );
+        assert_eq!(cut.cut_edges.iter().map(|e| e.2).sum::<u64>(), 4);
+        assert_eq!(cut.cut_edges.len(), 2);
+    }
+
+    #[test]
+    fn test_stoer_wagner_until() {
+        let graph = example_graph();
+        let cut = stoer_wagner_until(&graph, |w| w <= 4).unwrap();
+        assert_eq!(cut.weight, 4);
+        let mut cut_edges: Vec<(u8, u8, u64)> = cut
+            .cut_edges
+            .iter()
+            .map(|&(a, b, w)| (a.min(b), a.max(b), w))
+            .collect();
+        cut_edges.sort();
+        assert_eq!(cut_edges, vec![(2, 3, 3), (6, 7, 1)]);
+        for (a, b, _) in &cut.cut_edges {
+            assert!(cut.partitions[0].contains(a));
+            assert!(cut.partitions[1].contains(b));
+        }
+
+        // Stopping at the first phase gives some cut, not necessarily the minimum.
+        let cut = stoer_wagner_until(&graph, |_| true).unwrap();
+        assert_eq!(cut.weight, cut.cut_edges.iter().map(|e| e.2).sum::<u64>());
+    }
+
+    #[test]
+    fn test_karger_stein() {
+        let cut = karger_stein(&example_graph(), 25).unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(
+            sorted_partitions(&cut),
+            [vec![1, 2, 5, 6], vec![3, 4, 7, 8]]
+        );
+    }
+
+    #[test]
+    fn test_random_graphs_agree() {
+        let mut rng = XorShift::new(7);
+        for seed in 0..20 {
+            let mut graph = UndirectedWeightedGraph::new();
+            let n = 2 + rng.below(12) as u8;
+            for a in 0..n {
+                for b in (a + 1)..n {
+                    if rng.chance(1, 3) {
+                        graph.add_edge(a, b, 1 + rng.below(5));
+                    }
+                }
+            }
+            let Some(exact) = stoer_wagner(&graph) else {
+                continue;
+            };
+            let randomized = karger_stein(&graph, seed).unwrap();
+            assert_eq!(exact.weight, randomized.weight);
+            assert_eq!(
+                exact.weight,
+                exact.cut_edges.iter().map(|e| e.2).sum::<u64>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let mut graph = UndirectedWeightedGraph::new();
+        graph.add_edge('a', 'b', 5);
+        graph.add_edge('c', 'd', 5);
+        assert_eq!(stoer_wagner(&graph).unwrap().weight, 0);
+        assert_eq!(karger_stein(&graph, 0).unwrap().weight, 0);
+        assert!(stoer_wagner(&UndirectedWeightedGraph::<char>::new()).is_none());
+    }
+}
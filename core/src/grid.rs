@@ -1,5 +1,5 @@
 use {
-    crate::min_heap::MinHeap,
+    crate::priority_queue::{BucketQueue, IndexedMinHeap},
     std::{
         cmp::Ordering,
         collections::hash_map::{Entry, HashMap},
@@ -27,31 +27,28 @@ where
     F: Fn(&Node, &Node) -> u64,
     G: Fn(&Node) -> I,
 {
-    let mut heap: MinHeap<(u64, Node)> = MinHeap::new();
-    heap.push((0, start.clone()));
+    let mut heap: IndexedMinHeap<Node, u64> = IndexedMinHeap::new();
+    heap.push(start.clone(), 0);
 
     let mut distances: HashMap<Node, u64> = HashMap::new();
     distances.insert(start, 0);
 
     let mut prev: HashMap<Node, Vec<Node>> = HashMap::new();
 
-    while let Some((dist, u)) = heap.pop() {
-        if dist > distances[&u] {
-            continue;
-        }
+    while let Some((u, dist)) = heap.pop() {
         for v in neighbors(&u) {
             let alt = dist + weights(&u, &v);
             match distances.entry(v.clone()) {
                 Entry::Vacant(entry) => {
                     entry.insert(alt);
                     prev.insert(v.clone(), vec![u.clone()]);
-                    heap.push((alt, v));
+                    heap.push(v, alt);
                 }
                 Entry::Occupied(mut entry) => match alt.cmp(entry.get()) {
                     Ordering::Less => {
                         entry.insert(alt);
                         prev.insert(v.clone(), vec![u.clone()]);
-                        heap.push((alt, v));
+                        heap.decrease_key(v, alt);
                     }
                     Ordering::Equal => {
                         prev.get_mut(&v).expect("Other prev exists").push(u.clone());
@@ -65,6 +62,43 @@ where
     (distances, prev)
 }
 
+// Distances from `start` as in `dijkstra_shortest_path`, for small integer
+// weights such as unit steps on a grid. Uses a bucket queue in place of a heap.
+pub fn dial_shortest_path<Node, I, F, G>(
+    start: Node,
+    weights: F,
+    neighbors: G,
+) -> HashMap<Node, u64>
+where
+    Node: Clone + Hash + Eq,
+    I: IntoIterator<Item = Node>,
+    F: Fn(&Node, &Node) -> u64,
+    G: Fn(&Node) -> I,
+{
+    let mut queue = BucketQueue::new();
+    queue.push(0, start.clone());
+
+    let mut distances: HashMap<Node, u64> = HashMap::new();
+    distances.insert(start, 0);
+
+    while let Some((dist, u)) = queue.pop() {
+        let dist = dist as u64;
+        // Nodes are pushed again when a shorter path is found, skip the old entry.
+        if dist > distances[&u] {
+            continue;
+        }
+        for v in neighbors(&u) {
+            let alt = dist + weights(&u, &v);
+            if distances.get(&v).is_none_or(|d| alt < *d) {
+                distances.insert(v.clone(), alt);
+                queue.push(alt as usize, v);
+            }
+        }
+    }
+
+    distances
+}
+
 pub struct NeighborsCreator {
     pub n_rows: usize,
     pub n_cols: usize,
@@ -0,0 +1,205 @@
+use std::ops::RangeInclusive;
+
+// Axis aligned box in `N` dimensions, given by an inclusive range of integers
+// along each axis. Boxes are never empty; operations which could produce an
+// empty box return `None` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Box<const N: usize> {
+    // `(min, max)` along each axis, with `min <= max`.
+    bounds: [(i64, i64); N],
+}
+
+// Condition on a single coordinate used to split a box in two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predicate {
+    LessThan(i64),
+    GreaterThan(i64),
+}
+
+impl Predicate {
+    pub fn matches(&self, x: i64) -> bool {
+        match self {
+            Self::LessThan(value) => x < *value,
+            Self::GreaterThan(value) => x > *value,
+        }
+    }
+}
+
+impl<const N: usize> Box<N> {
+    // Returns `None` if any of the ranges is empty.
+    pub fn new(ranges: [RangeInclusive<i64>; N]) -> Option<Self> {
+        let bounds = ranges.map(|r| r.into_inner());
+        Self::from_bounds(bounds)
+    }
+
+    fn from_bounds(bounds: [(i64, i64); N]) -> Option<Self> {
+        bounds
+            .iter()
+            .all(|(min, max)| min <= max)
+            .then_some(Self { bounds })
+    }
+
+    // Smallest box containing both corners.
+    pub fn from_corners(a: [i64; N], b: [i64; N]) -> Self {
+        Self {
+            bounds: std::array::from_fn(|i| (a[i].min(b[i]), a[i].max(b[i]))),
+        }
+    }
+
+    pub fn range(&self, axis: usize) -> RangeInclusive<i64> {
+        let (min, max) = self.bounds[axis];
+        min..=max
+    }
+
+    pub fn start(&self, axis: usize) -> i64 {
+        self.bounds[axis].0
+    }
+
+    pub fn end(&self, axis: usize) -> i64 {
+        self.bounds[axis].1
+    }
+
+    // Number of integer points in the box.
+    pub fn volume(&self) -> u128 {
+        self.bounds
+            .iter()
+            .map(|(min, max)| (max - min) as u128 + 1)
+            .product()
+    }
+
+    pub fn contains(&self, point: &[i64; N]) -> bool {
+        self.bounds
+            .iter()
+            .zip(point)
+            .all(|((min, max), x)| min <= x && x <= max)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::from_bounds(std::array::from_fn(|i| {
+            let (a_min, a_max) = self.bounds[i];
+            let (b_min, b_max) = other.bounds[i];
+            (a_min.max(b_min), a_max.min(b_max))
+        }))
+    }
+
+    // Split into the part below `value` and the part at or above `value` along `axis`.
+    pub fn split_at(&self, axis: usize, value: i64) -> (Option<Self>, Option<Self>) {
+        let (min, max) = self.bounds[axis];
+        let mut below = self.bounds;
+        below[axis] = (min, max.min(value - 1));
+        let mut above = self.bounds;
+        above[axis] = (min.max(value), max);
+        (Self::from_bounds(below), Self::from_bounds(above))
+    }
+
+    // Split into the part whose coordinate along `axis` matches the predicate
+    // and the part which does not.
+    pub fn partition(&self, axis: usize, predicate: Predicate) -> (Option<Self>, Option<Self>) {
+        match predicate {
+            Predicate::LessThan(value) => self.split_at(axis, value),
+            Predicate::GreaterThan(value) => {
+                let (below, above) = self.split_at(axis, value + 1);
+                (above, below)
+            }
+        }
+    }
+
+    // The points of `self` which are not in `other`, as at most `2 * N` disjoint boxes.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut result = Vec::new();
+        let mut remainder = *self;
+        // Peel off the slabs on either side of the overlap, one axis at a time.
+        for axis in 0..N {
+            let (below, rest) = remainder.split_at(axis, overlap.start(axis));
+            let (rest, above) = rest
+                .expect("Overlap is inside remainder")
+                .split_at(axis, overlap.end(axis) + 1);
+            result.extend(below);
+            result.extend(above);
+            remainder = rest.expect("Overlap is inside remainder");
+        }
+        result
+    }
+
+    // Move the box by `delta` along `axis`.
+    pub fn translate(&self, axis: usize, delta: i64) -> Self {
+        let mut bounds = self.bounds;
+        bounds[axis] = (bounds[axis].0 + delta, bounds[axis].1 + delta);
+        Self { bounds }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(b: &Box<2>) -> Vec<[i64; 2]> {
+        b.range(0)
+            .flat_map(|x| b.range(1).map(move |y| [x, y]))
+            .collect()
+    }
+
+    #[test]
+    fn test_basics() {
+        let b = Box::new([1..=4000, 1..=4000, 1..=4000, 1..=4000]).unwrap();
+        assert_eq!(b.volume(), 4000_u128.pow(4));
+        let (start, end) = (1, 0);
+        assert!(Box::new([start..=end, 1..=2]).is_none());
+        assert_eq!(
+            Box::from_corners([3, 1], [1, 4]),
+            Box::new([1..=3, 1..=4]).unwrap()
+        );
+
+        let a = Box::new([0..=2, 0..=2]).unwrap();
+        let c = Box::new([2..=5, 1..=1]).unwrap();
+        assert_eq!(a.intersection(&c), Box::new([2..=2, 1..=1]));
+        assert!(!a.intersects(&c.translate(1, 2)));
+        assert!(a.contains(&[2, 0]) && !a.contains(&[3, 0]));
+    }
+
+    #[test]
+    fn test_partition() {
+        let b = Box::new([1..=10, 1..=10]).unwrap();
+        let (lt, rest) = b.partition(0, Predicate::LessThan(4));
+        assert_eq!(lt, Box::new([1..=3, 1..=10]));
+        assert_eq!(rest, Box::new([4..=10, 1..=10]));
+        let (gt, rest) = b.partition(1, Predicate::GreaterThan(9));
+        assert_eq!(gt, Box::new([1..=10, 10..=10]));
+        assert_eq!(rest, Box::new([1..=10, 1..=9]));
+        assert_eq!(b.partition(1, Predicate::GreaterThan(10)), (None, Some(b)));
+        assert_eq!(b.partition(0, Predicate::LessThan(1)), (None, Some(b)));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = Box::new([0..=5, 0..=5]).unwrap();
+        for other in [
+            Box::new([2..=3, 2..=3]).unwrap(),
+            Box::new([-3..=2, 4..=9]).unwrap(),
+            Box::new([0..=5, 0..=5]).unwrap(),
+            Box::new([7..=8, 0..=5]).unwrap(),
+            Box::new([1..=1, -4..=10]).unwrap(),
+        ] {
+            let pieces = a.subtract(&other);
+            assert!(pieces.len() <= 4);
+            let mut covered: Vec<[i64; 2]> = pieces.iter().flat_map(points).collect();
+            let total = covered.len();
+            covered.sort_unstable();
+            covered.dedup();
+            // Pieces are disjoint
+            assert_eq!(covered.len(), total);
+            let expected: Vec<[i64; 2]> = points(&a)
+                .into_iter()
+                .filter(|p| !other.contains(p))
+                .collect();
+            assert_eq!(covered, expected);
+        }
+    }
+}
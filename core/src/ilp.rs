@@ -0,0 +1,464 @@
+use crate::rational::Rational;
+
+type R = Rational<i128>;
+
+// Largest bound derived for variables without an explicit upper bound, which
+// keeps the rational arithmetic well away from overflowing.
+const MAX_DERIVED_BOUND: u128 = 1 << 32;
+
+// Integer linear program: minimize `objective . x` subject to `Ax = b`,
+// `0 <= x <= upper_bounds` and `x` integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegerProgram {
+    objective: Vec<i64>,
+    constraints: Vec<(Vec<i64>, i64)>,
+    upper_bounds: Vec<Option<i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IlpSolution {
+    pub value: i64,
+    pub assignment: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlpError {
+    Infeasible,
+    Unbounded,
+    // Some variable has no explicit upper bound and the bound derived for it
+    // is too large to search safely. Add one with `upper_bound`.
+    NeedsBounds,
+}
+
+// Outcome of solving the linear relaxation of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LpOutcome {
+    Optimal { value: R, assignment: Vec<R> },
+    Infeasible,
+    Unbounded,
+}
+
+impl IntegerProgram {
+    pub fn new(objective: Vec<i64>) -> Self {
+        let n = objective.len();
+        Self {
+            objective,
+            constraints: Vec::new(),
+            upper_bounds: vec![None; n],
+        }
+    }
+
+    pub fn n_vars(&self) -> usize {
+        self.objective.len()
+    }
+
+    // Add the constraint `coefficients . x = rhs`.
+    pub fn equality(mut self, coefficients: Vec<i64>, rhs: i64) -> Self {
+        assert_eq!(
+            coefficients.len(),
+            self.n_vars(),
+            "One coefficient per variable"
+        );
+        self.constraints.push((coefficients, rhs));
+        self
+    }
+
+    pub fn upper_bound(mut self, var: usize, bound: i64) -> Self {
+        let current = self.upper_bounds[var].get_or_insert(bound);
+        *current = (*current).min(bound);
+        self
+    }
+
+    // Branch and bound using the simplex method on the linear relaxation.
+    // Variables without an upper bound are given one from `derived_upper_bounds`,
+    // so the search terminates even when there is no integer solution.
+    pub fn solve(&self) -> Result<IlpSolution, IlpError> {
+        let n = self.n_vars();
+        let mut best: Option<IlpSolution> = None;
+        let mut stack = vec![(vec![0; n], self.derived_upper_bounds()?)];
+        while let Some((lower, upper)) = stack.pop() {
+            let (value, assignment) = match self.relaxation(&lower, &upper) {
+                LpOutcome::Infeasible => continue,
+                LpOutcome::Unbounded => unreachable!("Every variable is bounded"),
+                LpOutcome::Optimal { value, assignment } => (value, assignment),
+            };
+
+            // The objective has integer coefficients, so integer solutions have integer values.
+            let bound = value.ceil() as i64;
+            if best.as_ref().is_some_and(|b| bound >= b.value) {
+                continue;
+            }
+
+            let Some((var, x)) = assignment.iter().enumerate().find(|(_, x)| !x.is_integer())
+            else {
+                best = Some(IlpSolution {
+                    value: bound,
+                    assignment: assignment.iter().map(|x| x.numerator() as i64).collect(),
+                });
+                continue;
+            };
+
+            // Split on the fractional variable. The "round up" branch is explored first
+            // since it is pushed last.
+            let mut down = upper.clone();
+            down[var] = Some(x.floor() as i64);
+            stack.push((lower.clone(), down));
+            let mut up = lower;
+            up[var] = x.ceil() as i64;
+            stack.push((up, upper));
+        }
+
+        // With an integer solution and an unbounded relaxation the integer program
+        // is unbounded too (Meyer, 1974), even though the search above was bounded.
+        let best = best.ok_or(IlpError::Infeasible)?;
+        if self.relaxation(&vec![0; n], &self.upper_bounds) == LpOutcome::Unbounded {
+            return Err(IlpError::Unbounded);
+        }
+        Ok(best)
+    }
+
+    // If the program has an optimal solution then it has one with every component
+    // at most `n (m a)^(2m + 1)`, where `A` is `m x n` and `a` is the largest
+    // absolute value in `A`, `b` and the objective (Papadimitriou, 1981). The
+    // objective counts as an extra row, and each explicit upper bound as a row
+    // `x_j + s_j = u` with a slack variable. Fails if a variable needs a bound
+    // above `MAX_DERIVED_BOUND`.
+    fn derived_upper_bounds(&self) -> Result<Vec<Option<i64>>, IlpError> {
+        if self.upper_bounds.iter().all(Option::is_some) {
+            return Ok(self.upper_bounds.clone());
+        }
+        let explicit: Vec<i64> = self.upper_bounds.iter().flatten().copied().collect();
+        let n = (self.n_vars() + explicit.len()) as u128;
+        let m = (self.constraints.len() + explicit.len() + 1) as u128;
+        let a = self
+            .constraints
+            .iter()
+            .flat_map(|(coefficients, rhs)| coefficients.iter().chain(std::iter::once(rhs)))
+            .chain(&self.objective)
+            .chain(&explicit)
+            .map(|x| x.unsigned_abs() as u128)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let bound = n.saturating_mul(m.saturating_mul(a).saturating_pow(2 * m as u32 + 1));
+        if bound > MAX_DERIVED_BOUND {
+            return Err(IlpError::NeedsBounds);
+        }
+        Ok(self
+            .upper_bounds
+            .iter()
+            .map(|u| Some(u.unwrap_or(bound as i64)))
+            .collect())
+    }
+
+    // Solve the linear program with the integrality constraint dropped
+    // and the given variable bounds.
+    pub fn relaxation(&self, lower: &[i64], upper: &[Option<i64>]) -> LpOutcome {
+        let n = self.n_vars();
+        if lower
+            .iter()
+            .zip(upper)
+            .any(|(l, u)| u.is_some_and(|u| u < *l))
+        {
+            return LpOutcome::Infeasible;
+        }
+
+        // Substitute `x = lower + y` so that `y >= 0`, and add a slack
+        // variable for each upper bound: `y + s = upper - lower`.
+        let bounded: Vec<usize> = (0..n).filter(|j| upper[*j].is_some()).collect();
+        let n_cols = n + bounded.len();
+        let mut rows: Vec<Vec<R>> = Vec::new();
+        for (coefficients, rhs) in &self.constraints {
+            let shift: i128 = coefficients
+                .iter()
+                .zip(lower)
+                .map(|(a, l)| (*a as i128) * (*l as i128))
+                .sum();
+            let mut row: Vec<R> = coefficients
+                .iter()
+                .map(|a| R::integer(*a as i128))
+                .collect();
+            row.resize(n_cols, R::ZERO);
+            row.push(R::integer(*rhs as i128 - shift));
+            rows.push(row);
+        }
+        for (k, j) in bounded.iter().enumerate() {
+            let mut row = vec![R::ZERO; n_cols + 1];
+            row[*j] = R::ONE;
+            row[n + k] = R::ONE;
+            row[n_cols] = R::integer((upper[*j].expect("Is bounded") - lower[*j]) as i128);
+            rows.push(row);
+        }
+
+        let mut costs: Vec<R> = self
+            .objective
+            .iter()
+            .map(|c| R::integer(*c as i128))
+            .collect();
+        costs.resize(n_cols, R::ZERO);
+        let Some(mut tableau) = Tableau::feasible(rows, n_cols) else {
+            return LpOutcome::Infeasible;
+        };
+        if !tableau.minimize(&costs) {
+            return LpOutcome::Unbounded;
+        }
+
+        let mut assignment: Vec<R> = lower.iter().map(|l| R::integer(*l as i128)).collect();
+        for (r, b) in tableau.basis.iter().enumerate() {
+            if *b < n {
+                assignment[*b] += tableau.rows[r][tableau.n_cols];
+            }
+        }
+        let value = assignment
+            .iter()
+            .zip(&self.objective)
+            .fold(R::ZERO, |acc, (x, c)| acc + *x * R::integer(*c as i128));
+        LpOutcome::Optimal { value, assignment }
+    }
+}
+
+// Simplex tableau for constraints `Ax = b, x >= 0`. The right hand side is the last column.
+// Bland's rule is used for pivoting, which guarantees termination.
+// See https://en.wikipedia.org/wiki/Simplex_algorithm
+#[derive(Debug, Clone)]
+struct Tableau {
+    rows: Vec<Vec<R>>,
+    basis: Vec<usize>,
+    // Reduced costs, with the negated objective value in the last column.
+    objective: Vec<R>,
+    n_cols: usize,
+}
+
+impl Tableau {
+    // Phase one: find a basic feasible solution using artificial variables.
+    // Returns `None` if the constraints are infeasible.
+    fn feasible(mut rows: Vec<Vec<R>>, n_cols: usize) -> Option<Self> {
+        let m = rows.len();
+        for row in rows.iter_mut() {
+            if row[n_cols] < R::ZERO {
+                for x in row.iter_mut() {
+                    *x = -*x;
+                }
+            }
+            // Insert artificial variable columns before the right hand side.
+            let rhs = row.pop().expect("Has right hand side");
+            row.resize(n_cols + m, R::ZERO);
+            row.push(rhs);
+        }
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[n_cols + i] = R::ONE;
+        }
+
+        let mut tableau = Self {
+            rows,
+            basis: (n_cols..(n_cols + m)).collect(),
+            objective: Vec::new(),
+            n_cols: n_cols + m,
+        };
+        let mut costs = vec![R::ZERO; n_cols];
+        costs.resize(n_cols + m, R::ONE);
+        tableau.minimize(&costs);
+        if !tableau.objective[tableau.n_cols].is_zero() {
+            return None;
+        }
+
+        // Drive any artificial variables (at value zero) out of the basis.
+        // If that is impossible then the row is redundant and can be dropped.
+        let mut r = 0;
+        while r < tableau.rows.len() {
+            if tableau.basis[r] < n_cols {
+                r += 1;
+                continue;
+            }
+            match (0..n_cols).find(|c| !tableau.rows[r][*c].is_zero()) {
+                Some(c) => {
+                    tableau.pivot(r, c);
+                    r += 1;
+                }
+                None => {
+                    tableau.rows.remove(r);
+                    tableau.basis.remove(r);
+                }
+            }
+        }
+
+        // Remove the artificial columns.
+        for row in tableau.rows.iter_mut() {
+            let rhs = row[tableau.n_cols];
+            row.truncate(n_cols);
+            row.push(rhs);
+        }
+        tableau.n_cols = n_cols;
+        Some(tableau)
+    }
+
+    fn pivot(&mut self, r: usize, c: usize) {
+        let leading = self.rows[r][c];
+        for x in self.rows[r].iter_mut() {
+            *x /= leading;
+        }
+        let pivot_row = self.rows[r].clone();
+        let eliminate = |row: &mut Vec<R>| {
+            let factor = row[c];
+            if !factor.is_zero() {
+                for (x, y) in row.iter_mut().zip(&pivot_row) {
+                    *x -= factor * *y;
+                }
+            }
+        };
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            if i != r {
+                eliminate(row);
+            }
+        }
+        if !self.objective.is_empty() {
+            eliminate(&mut self.objective);
+        }
+        self.basis[r] = c;
+    }
+
+    // Phase two: minimize `costs . x` starting from the current basis.
+    // Returns `false` if the objective is unbounded below.
+    fn minimize(&mut self, costs: &[R]) -> bool {
+        let mut objective = costs.to_vec();
+        objective.push(R::ZERO);
+        for (r, b) in self.basis.iter().enumerate() {
+            let factor = objective[*b];
+            if !factor.is_zero() {
+                for (x, y) in objective.iter_mut().zip(&self.rows[r]) {
+                    *x -= factor * *y;
+                }
+            }
+        }
+        self.objective = objective;
+
+        loop {
+            let Some(entering) = (0..self.n_cols).find(|c| self.objective[*c] < R::ZERO) else {
+                return true;
+            };
+            let leaving = (0..self.rows.len())
+                .filter(|r| self.rows[*r][entering] > R::ZERO)
+                .min_by_key(|r| {
+                    let ratio = self.rows[*r][self.n_cols] / self.rows[*r][entering];
+                    (ratio, self.basis[*r])
+                });
+            let Some(leaving) = leaving else {
+                return false;
+            };
+            self.pivot(leaving, entering);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::random::XorShift};
+
+    #[test]
+    fn test_relaxation() {
+        // Maximize x + y (minimize the negation) with 2x + y = 4, x + 2y <= 4.
+        let program = IntegerProgram::new(vec![-1, -1, 0])
+            .equality(vec![2, 1, 0], 4)
+            .equality(vec![1, 2, 1], 4);
+        let LpOutcome::Optimal { value, assignment } = program.relaxation(&[0; 3], &[None; 3])
+        else {
+            panic!("Expected optimal solution");
+        };
+        assert_eq!(value, R::new(-8, 3));
+        assert_eq!(assignment[0], R::new(4, 3));
+        assert_eq!(assignment[1], R::new(4, 3));
+    }
+
+    #[test]
+    fn test_integer_solution() {
+        // The relaxation above has a fractional optimum, but the integer optimum is (2, 0).
+        let program = IntegerProgram::new(vec![-1, -1, 0])
+            .equality(vec![2, 1, 0], 4)
+            .equality(vec![1, 2, 1], 4);
+        let solution = program.solve().unwrap();
+        assert_eq!(solution.value, -2);
+
+        let infeasible = IntegerProgram::new(vec![1]).equality(vec![2], 3);
+        assert_eq!(infeasible.solve(), Err(IlpError::Infeasible));
+
+        let unbounded = IntegerProgram::new(vec![-1, 0]).equality(vec![1, -1], 0);
+        assert_eq!(unbounded.solve(), Err(IlpError::Unbounded));
+    }
+
+    #[test]
+    fn test_unbounded_relaxation_without_integer_solution() {
+        // The relaxation has solutions all along `x = y + 1/2`, which branching
+        // alone would chase forever.
+        let program = IntegerProgram::new(vec![1, 1]).equality(vec![2, -2], 1);
+        assert_eq!(program.solve(), Err(IlpError::Infeasible));
+
+        // Maximizing over the same constraints is infeasible rather than unbounded.
+        let program = IntegerProgram::new(vec![-1, -1]).equality(vec![2, -2], 1);
+        assert_eq!(program.solve(), Err(IlpError::Infeasible));
+
+        // Larger programs need explicit bounds on the unbounded variables.
+        let program = IntegerProgram::new(vec![1, 1, 0])
+            .equality(vec![1, -1, 0], 25)
+            .equality(vec![0, 0, 1], 3);
+        assert_eq!(program.solve(), Err(IlpError::NeedsBounds));
+        let solution = program
+            .upper_bound(0, 100)
+            .upper_bound(1, 100)
+            .upper_bound(2, 100)
+            .solve()
+            .unwrap();
+        assert_eq!(solution.value, 25);
+        assert_eq!(solution.assignment, vec![25, 0, 3]);
+    }
+
+    // Compare against exhaustive search on small random programs.
+    #[test]
+    fn test_random_programs() {
+        let mut rng = XorShift::new(32);
+        for _ in 0..30 {
+            let n = 1 + rng.below(4) as usize;
+            let m = 1 + rng.below(3) as usize;
+            let max = 6;
+            let objective: Vec<i64> = (0..n).map(|_| rng.below(5) as i64).collect();
+            let rows: Vec<Vec<i64>> = (0..m)
+                .map(|_| (0..n).map(|_| rng.below(4) as i64).collect())
+                .collect();
+            let target: Vec<i64> = (0..n).map(|_| rng.below(max as u64) as i64).collect();
+            let mut program = IntegerProgram::new(objective.clone());
+            for row in &rows {
+                let rhs = row.iter().zip(&target).map(|(a, x)| a * x).sum();
+                program = program.equality(row.clone(), rhs);
+            }
+            for j in 0..n {
+                program = program.upper_bound(j, max);
+            }
+
+            let mut brute_force = None;
+            let mut x = vec![0; n];
+            loop {
+                let satisfied = rows
+                    .iter()
+                    .zip(&program.constraints)
+                    .all(|(row, (_, rhs))| {
+                        row.iter().zip(&x).map(|(a, x)| a * x).sum::<i64>() == *rhs
+                    });
+                if satisfied {
+                    let value: i64 = objective.iter().zip(&x).map(|(c, x)| c * x).sum();
+                    brute_force = Some(brute_force.map_or(value, |b: i64| b.min(value)));
+                }
+                // Advance to the next assignment
+                let Some(j) = x.iter().position(|v| *v < max) else {
+                    break;
+                };
+                x[j] += 1;
+                for v in x[..j].iter_mut() {
+                    *v = 0;
+                }
+            }
+
+            let solution = program.solve().unwrap();
+            assert_eq!(Some(solution.value), brute_force);
+            assert!(solution.assignment.iter().all(|x| (0..=max).contains(x)));
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::Hash,
+    ops::{Index, IndexMut},
+};
+
+// Dense identifier handed out by an `Interner`, or the position of a value in
+// an `IdVec`. Ids start at 0 and are allocated in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(u32);
+
+impl Id {
+    // Panics if `index` does not fit in a `u32`.
+    pub fn new(index: usize) -> Self {
+        Self(u32::try_from(index).expect("Id fits in u32"))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+// Assigns each distinct label an `Id`, so that e.g. node names can be
+// replaced by flat array indices before running a simulation.
+#[derive(Debug, Clone)]
+pub struct Interner<L> {
+    labels: Vec<L>,
+    ids: HashMap<L, Id>,
+}
+
+impl<L> Default for Interner<L> {
+    fn default() -> Self {
+        Self {
+            labels: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+}
+
+impl<L: Clone + Eq + Hash> Interner<L> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns the id of the label, allocating a new one if it has not been seen.
+    pub fn intern(&mut self, label: L) -> Id {
+        if let Some(id) = self.ids.get(&label) {
+            return *id;
+        }
+        let id = Id::new(self.labels.len());
+        self.labels.push(label.clone());
+        self.ids.insert(label, id);
+        id
+    }
+
+    pub fn get<Q>(&self, label: &Q) -> Option<Id>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.ids.get(label).copied()
+    }
+}
+
+impl<L> Interner<L> {
+    pub fn label(&self, id: Id) -> &L {
+        &self.labels[id.index()]
+    }
+
+    // All labels, indexed by id.
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = Id> {
+        (0..self.len()).map(Id::new)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &L)> {
+        self.labels.iter().enumerate().map(|(i, l)| (Id::new(i), l))
+    }
+}
+
+impl<L> Index<Id> for Interner<L> {
+    type Output = L;
+
+    fn index(&self, id: Id) -> &L {
+        self.label(id)
+    }
+}
+
+// Vector indexed by `Id`, holding a value for every id in `0..len`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdVec<T> {
+    values: Vec<T>,
+}
+
+impl<T> Default for IdVec<T> {
+    fn default() -> Self {
+        Self { values: Vec::new() }
+    }
+}
+
+impl<T> IdVec<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `len` copies of `value`, e.g. one per label of an interner.
+    pub fn filled(len: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            values: vec![value; len],
+        }
+    }
+
+    // Returns the id of the new value.
+    pub fn push(&mut self, value: T) -> Id {
+        self.values.push(value);
+        Id::new(self.values.len() - 1)
+    }
+
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.values.get(id.index())
+    }
+
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.values.get_mut(id.index())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = Id> {
+        (0..self.len()).map(Id::new)
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.values.iter().enumerate().map(|(i, x)| (Id::new(i), x))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Id, &mut T)> {
+        self.values
+            .iter_mut()
+            .enumerate()
+            .map(|(i, x)| (Id::new(i), x))
+    }
+}
+
+impl<T> Index<Id> for IdVec<T> {
+    type Output = T;
+
+    fn index(&self, id: Id) -> &T {
+        &self.values[id.index()]
+    }
+}
+
+impl<T> IndexMut<Id> for IdVec<T> {
+    fn index_mut(&mut self, id: Id) -> &mut T {
+        &mut self.values[id.index()]
+    }
+}
+
+impl<T> From<Vec<T>> for IdVec<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self { values }
+    }
+}
+
+impl<T> FromIterator<T> for IdVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+// Map from `Id` to values for some of the ids, stored as a flat vector.
+// Best when the ids come from a small interner.
+#[derive(Debug, Clone)]
+pub struct IdMap<T> {
+    values: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> Default for IdMap<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> IdMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns the previous value for the id, if any.
+    pub fn insert(&mut self, id: Id, value: T) -> Option<T> {
+        if id.index() >= self.values.len() {
+            self.values.resize_with(id.index() + 1, || None);
+        }
+        let old = self.values[id.index()].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, id: Id) -> Option<T> {
+        let old = self.values.get_mut(id.index())?.take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.values.get(id.index())?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.values.get_mut(id.index())?.as_mut()
+    }
+
+    pub fn contains_key(&self, id: Id) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Entries in increasing order of id.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, x)| Some((Id::new(i), x.as_ref()?)))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = Id> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.values.iter().flatten()
+    }
+}
+
+impl<T: PartialEq> PartialEq for IdMap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for IdMap<T> {}
+
+// Panics if the id is not in the map.
+impl<T> Index<Id> for IdMap<T> {
+    type Output = T;
+
+    fn index(&self, id: Id) -> &T {
+        self.get(id).expect("Id is in map")
+    }
+}
+
+impl<T> IndexMut<Id> for IdMap<T> {
+    fn index_mut(&mut self, id: Id) -> &mut T {
+        self.get_mut(id).expect("Id is in map")
+    }
+}
+
+impl<T> FromIterator<(Id, T)> for IdMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Id, T)>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for (id, value) in iter {
+            result.insert(id, value);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut labels = Interner::new();
+        let a = labels.intern("broadcaster");
+        let b = labels.intern("rx");
+        assert_eq!(labels.intern("broadcaster"), a);
+        assert_eq!((a.index(), b.index()), (0, 1));
+        assert_eq!(labels.get("rx"), Some(b));
+        assert_eq!(labels.get("output"), None);
+        assert_eq!(labels[b], "rx");
+        assert_eq!(labels.labels(), ["broadcaster", "rx"]);
+
+        let mut owned: Interner<String> = Interner::new();
+        let id = owned.intern("abc".to_string());
+        assert_eq!(owned.get("abc"), Some(id));
+    }
+
+    #[test]
+    fn test_id_vec() {
+        let mut pulses: IdVec<u32> = IdVec::filled(2, 0);
+        let c = pulses.push(5);
+        pulses[Id::new(1)] += 3;
+        assert_eq!(pulses.values(), [0, 3, 5]);
+        assert_eq!(pulses.get(c), Some(&5));
+        assert_eq!(pulses.get(Id::new(3)), None);
+        let big: Vec<Id> = pulses
+            .iter()
+            .filter(|(_, x)| **x > 1)
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(big, vec![Id::new(1), c]);
+    }
+
+    #[test]
+    fn test_id_map() {
+        let mut memory: IdMap<bool> = IdMap::new();
+        assert_eq!(memory.insert(Id::new(4), true), None);
+        assert_eq!(memory.insert(Id::new(1), false), None);
+        assert_eq!(memory.insert(Id::new(4), false), Some(true));
+        assert_eq!(memory.len(), 2);
+        assert!(memory.contains_key(Id::new(1)));
+        assert!(!memory.contains_key(Id::new(2)));
+        assert_eq!(
+            memory.keys().collect::<Vec<_>>(),
+            vec![Id::new(1), Id::new(4)]
+        );
+        memory[Id::new(1)] = true;
+        assert_eq!(memory.remove(Id::new(4)), Some(false));
+        assert_eq!(memory.remove(Id::new(7)), None);
+
+        // Equality ignores how far the storage has grown.
+        let other: IdMap<bool> = [(Id::new(1), true)].into_iter().collect();
+        assert_eq!(memory, other);
+    }
+}
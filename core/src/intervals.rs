@@ -0,0 +1,353 @@
+use {
+    crate::num::Integer,
+    std::ops::{Range, RangeInclusive},
+};
+
+// Set of values stored as sorted, disjoint, non-adjacent half-open intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Range<T>>,
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    fn normalized(mut intervals: Vec<Range<T>>) -> Self {
+        intervals.retain(|r| r.start < r.end);
+        intervals.sort_unstable_by_key(|r| r.start);
+        let mut result: Vec<Range<T>> = Vec::with_capacity(intervals.len());
+        for r in intervals {
+            match result.last_mut() {
+                // Overlapping or touching intervals are merged.
+                Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+                _ => result.push(r),
+            }
+        }
+        Self { intervals: result }
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(range);
+        *self = Self::normalized(intervals);
+    }
+
+    // Panics if `range.end()` is the largest value of `T`.
+    pub fn insert_inclusive(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        let end = end
+            .checked_add(T::ONE)
+            .expect("Inclusive range end overflows");
+        self.insert(start..end);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // Total number of values in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |acc, r| acc + (r.end - r.start))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|r| r.end - T::ONE)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        let i = self.intervals.partition_point(|r| r.end <= *x);
+        self.intervals.get(i).is_some_and(|r| r.start <= *x)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.intervals.iter().cloned()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.iter().chain(other.iter()).collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                result.push(start..end);
+            }
+            // Advance whichever interval finishes first.
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for a in &self.intervals {
+            let mut start = a.start;
+            // Skip the intervals of `other` which end before this one starts.
+            while other.intervals.get(j).is_some_and(|b| b.end <= start) {
+                j += 1;
+            }
+            let mut k = j;
+            while let Some(b) = other.intervals.get(k) {
+                if b.start >= a.end {
+                    break;
+                }
+                if start < b.start {
+                    result.push(start..b.start);
+                }
+                start = start.max(b.end);
+                k += 1;
+            }
+            if start < a.end {
+                result.push(start..a.end);
+            }
+        }
+        Self { intervals: result }
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().collect())
+    }
+}
+
+impl<T: Integer> From<Range<T>> for IntervalSet<T> {
+    fn from(value: Range<T>) -> Self {
+        Self::normalized(vec![value])
+    }
+}
+
+// Function which shifts each of a collection of disjoint source intervals
+// by its own offset, and leaves every value outside of them unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OffsetMap<T> {
+    // Sorted by source start, with `(source, destination start)` pairs.
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T: Integer> Default for OffsetMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Integer> OffsetMap<T> {
+    // The identity map.
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    fn domain(&self) -> IntervalSet<T> {
+        self.pieces.iter().map(|(r, _)| r.clone()).collect()
+    }
+
+    // Map `source` onto the interval starting at `destination`. Values which
+    // are already part of an existing piece keep their existing mapping.
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        let uncovered = IntervalSet::from(source.clone()).difference(&self.domain());
+        for r in uncovered.iter() {
+            let shifted = destination + (r.start - source.start);
+            self.pieces.push((r, shifted));
+        }
+        self.pieces.sort_unstable_by_key(|(r, _)| r.start);
+    }
+
+    pub fn apply(&self, x: T) -> T {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= x);
+        match self.pieces.get(i) {
+            Some((r, d)) if r.start <= x => *d + (x - r.start),
+            _ => x,
+        }
+    }
+
+    // Calls `f(source, destination start)` for every maximal part of `range`
+    // on which the map is a single shift, including the identity parts.
+    fn for_each_piece_in<F: FnMut(Range<T>, T)>(&self, range: Range<T>, mut f: F) {
+        let mut start = range.start;
+        let i = self.pieces.partition_point(|(r, _)| r.end <= start);
+        for (r, d) in &self.pieces[i..] {
+            if r.start >= range.end {
+                break;
+            }
+            if start < r.start {
+                f(start..r.start, start);
+            }
+            let overlap_start = start.max(r.start);
+            let overlap_end = range.end.min(r.end);
+            f(overlap_start..overlap_end, *d + (overlap_start - r.start));
+            start = overlap_end;
+        }
+        if start < range.end {
+            f(start..range.end, start);
+        }
+    }
+
+    // Image of a whole set under the map.
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = Vec::new();
+        for range in set.iter() {
+            self.for_each_piece_in(range, |r, d| result.push(d..(d + (r.end - r.start))));
+        }
+        IntervalSet::normalized(result)
+    }
+
+    // The map which applies `self` first and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+        // Values moved by `self` are then moved by whichever pieces of `next`
+        // cover their image.
+        for (r, d) in &self.pieces {
+            let image = *d..(*d + (r.end - r.start));
+            next.for_each_piece_in(image, |sub, e| {
+                let source_start = r.start + (sub.start - *d);
+                pieces.push((source_start..(source_start + (sub.end - sub.start)), e));
+            });
+        }
+        // Values fixed by `self` are only moved by `next`.
+        let domain = self.domain();
+        for (r, e) in &next.pieces {
+            let fixed = IntervalSet::from(r.clone()).difference(&domain);
+            for sub in fixed.iter() {
+                pieces.push((sub.clone(), *e + (sub.start - r.start)));
+            }
+        }
+        // Drop the pieces which turned out to be the identity.
+        pieces.retain(|(r, d)| r.start != *d);
+        pieces.sort_unstable_by_key(|(r, _)| r.start);
+        Self { pieces }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::random::XorShift};
+
+    fn set(ranges: &[Range<u64>]) -> IntervalSet<u64> {
+        ranges.iter().cloned().collect()
+    }
+
+    fn brute_force(set: &IntervalSet<u64>) -> Vec<bool> {
+        (0..64).map(|x| set.contains(&x)).collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let s = set(&[5..7, 1..3, 3..4, 10..10, 6..9]);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![1..4, 5..9]);
+        assert_eq!(s.len(), 7);
+        assert_eq!(s.min(), Some(1));
+        assert_eq!(s.max(), Some(8));
+        assert!(s.contains(&3) && !s.contains(&4) && s.contains(&8) && !s.contains(&9));
+
+        let mut s = IntervalSet::new();
+        s.insert_inclusive(3..=5);
+        s.insert_inclusive(10..=14);
+        s.insert_inclusive(16..=20);
+        s.insert_inclusive(12..=18);
+        assert_eq!(s.len(), 14);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut rng = XorShift::new(39);
+        let random_set = |rng: &mut XorShift| -> IntervalSet<u64> {
+            (0..rng.below(6))
+                .map(|_| {
+                    let start = rng.below(60);
+                    start..(start + rng.below(10))
+                })
+                .collect()
+        };
+        for _ in 0..200 {
+            let a = random_set(&mut rng);
+            let b = random_set(&mut rng);
+            let (xs, ys) = (brute_force(&a), brute_force(&b));
+            let expect = |f: fn(bool, bool) -> bool| -> Vec<bool> {
+                xs.iter().zip(&ys).map(|(x, y)| f(*x, *y)).collect()
+            };
+            assert_eq!(brute_force(&a.union(&b)), expect(|x, y| x || y));
+            assert_eq!(brute_force(&a.intersection(&b)), expect(|x, y| x && y));
+            assert_eq!(brute_force(&a.difference(&b)), expect(|x, y| x && !y));
+            assert_eq!(a.is_subset(&b), xs.iter().zip(&ys).all(|(x, y)| !x || *y));
+            // Results are normalized, so equal sets compare equal.
+            assert_eq!(a.union(&b), b.union(&a));
+        }
+    }
+
+    #[test]
+    fn test_offset_map() {
+        let mut seed_to_soil = OffsetMap::new();
+        seed_to_soil.insert(98..100, 50);
+        seed_to_soil.insert(50..98, 52);
+        // Overlaps the existing pieces, so only 100..102 is added.
+        seed_to_soil.insert(96..102, 0);
+        assert_eq!(seed_to_soil.apply(79), 81);
+        assert_eq!(seed_to_soil.apply(14), 14);
+        assert_eq!(seed_to_soil.apply(99), 51);
+        assert_eq!(seed_to_soil.apply(101), 5);
+
+        let seeds = set(&[79..93, 55..68]);
+        assert_eq!(seed_to_soil.apply_set(&seeds), set(&[81..95, 57..70]));
+        assert_eq!(
+            seed_to_soil.apply_set(&IntervalSet::from(97..101)),
+            set(&[4..5, 50..52, 99..100])
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        let mut rng = XorShift::new(5);
+        let random_map = |rng: &mut XorShift| -> OffsetMap<u64> {
+            let mut map = OffsetMap::new();
+            for _ in 0..rng.below(5) {
+                let start = rng.below(50);
+                map.insert(start..(start + rng.below(10)), rng.below(50));
+            }
+            map
+        };
+        for _ in 0..200 {
+            let f = random_map(&mut rng);
+            let g = random_map(&mut rng);
+            let h = f.then(&g);
+            for x in 0..70 {
+                assert_eq!(h.apply(x), g.apply(f.apply(x)));
+            }
+            let s = set(&[3..20, 30..45]);
+            let image: IntervalSet<u64> = s
+                .iter()
+                .flatten()
+                .map(|x| h.apply(x)..(h.apply(x) + 1))
+                .collect();
+            assert_eq!(h.apply_set(&s), image);
+        }
+    }
+}
@@ -1,28 +1,100 @@
-use std::{array::IntoIter, iter::Flatten, ops::Range, slice::Iter};
+use {
+    crate::small_vec::{self, SmallVec},
+    std::{
+        ops::{Deref, DerefMut},
+        slice::Iter,
+    },
+};
+
+// Mixed-radix counter over all points of a hyper rectangular prism, where
+// digit `i` ranges over `0..maxes[i]` and the last digit changes fastest.
+// Points are lent out as slices, so visiting them does not allocate:
+//
+//     while let Some(point) = odometer.next() { ... }
+//
+// Calling `skip_prefix(k)` after a point has been visited prunes every
+// remaining point which shares its first `k` digits.
+#[derive(Debug, Clone)]
+pub struct Odometer {
+    maxes: Vec<u32>,
+    state: Vec<u32>,
+    // Number of leading digits kept fixed by the next advance.
+    fixed: usize,
+    started: bool,
+    done: bool,
+}
+
+impl Odometer {
+    // Has no points if any of the maxes are 0.
+    pub fn new(maxes: Vec<u32>) -> Self {
+        let n = maxes.len();
+        Self {
+            done: maxes.contains(&0),
+            state: vec![0; n],
+            fixed: n,
+            started: false,
+            maxes,
+        }
+    }
+
+    // Total number of points, or `None` if it does not fit in a `u128`.
+    pub fn len(&self) -> Option<u128> {
+        self.maxes
+            .iter()
+            .try_fold(1_u128, |acc, x| acc.checked_mul(*x as u128))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.maxes.contains(&0)
+    }
+
+    // The most recently visited point.
+    pub fn current(&self) -> Option<&[u32]> {
+        (self.started && !self.done).then_some(&self.state)
+    }
+
+    // Skip the remaining points whose first `k` digits equal those of the
+    // current point. `skip_prefix(0)` skips everything.
+    pub fn skip_prefix(&mut self, k: usize) {
+        self.fixed = self.fixed.min(k);
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[u32]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            return Some(&self.state);
+        }
+        let fixed = std::mem::replace(&mut self.fixed, self.maxes.len());
+        self.state[fixed..].fill(0);
+        // Increment the last digit of the prefix, carrying to the left.
+        for i in (0..fixed).rev() {
+            self.state[i] += 1;
+            if self.state[i] < self.maxes[i] {
+                return Some(&self.state);
+            }
+            self.state[i] = 0;
+        }
+        self.done = true;
+        None
+    }
+}
 
 // Visit all natural number points in a hyper rectangular prism.
+// Allocates a new `Vec` per point; use `Odometer` to avoid that.
 #[derive(Debug)]
 pub struct DynCartesianProduct {
-    maxes: Vec<u32>,
-    iters: Vec<Range<u32>>,
-    state: Vec<u32>,
-    len: u32,
-    count: u32,
+    odometer: Odometer,
 }
 
 impl DynCartesianProduct {
     // Returns None if any of the maxes are 0.
     pub fn new(maxes: Vec<u32>) -> Option<Self> {
-        let len: u32 = maxes.iter().copied().product();
-        let mut iters: Vec<Range<u32>> = maxes.iter().map(|x| 0..*x).collect();
-        let state: Option<Vec<u32>> = iters.iter_mut().map(|xs| xs.next()).collect();
-        Some(Self {
-            maxes,
-            iters,
-            state: state?,
-            len,
-            count: 0,
-        })
+        let odometer = Odometer::new(maxes);
+        (!odometer.is_empty()).then_some(Self { odometer })
     }
 }
 
@@ -30,32 +102,7 @@ impl Iterator for DynCartesianProduct {
     type Item = Vec<u32>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.count == self.len {
-            return None;
-        }
-
-        let result = self.state.clone();
-        let update_iter = self
-            .state
-            .iter_mut()
-            .rev()
-            .zip(self.iters.iter_mut().enumerate().rev());
-
-        for (x, (i, range)) in update_iter {
-            match range.next() {
-                Some(new_value) => {
-                    *x = new_value;
-                    break;
-                }
-                None => {
-                    *range = 0..(self.maxes[i]);
-                    *x = range.next().expect("All ranges are non-empty");
-                }
-            }
-        }
-
-        self.count += 1;
-        Some(result)
+        self.odometer.next().map(<[u32]>::to_vec)
     }
 }
 
@@ -165,64 +212,296 @@ where
     }
 }
 
-pub struct AtMost<T, const N: usize> {
-    pub inner: [Option<T>; N],
-}
+// Up to `N` values stored inline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AtMost<T, const N: usize>(SmallVec<T, N>);
 
 impl<T, const N: usize> AtMost<T, N> {
     pub fn new(xs: [T; N]) -> Self {
-        let mut inner = [const { None }; N];
-        for (x, i) in xs.into_iter().zip(inner.iter_mut()) {
-            *i = Some(x);
-        }
-        Self { inner }
+        Self(xs.into())
     }
 
     pub fn one(x: T) -> Self {
-        let mut inner = [const { None }; N];
-        inner[0] = Some(x);
-        Self { inner }
+        Self::some([x])
     }
 
+    // Keeps the first `N` values of `xs`.
     pub fn some<I>(xs: I) -> Self
     where
         I: IntoIterator<Item = T>,
     {
-        let mut inner = [const { None }; N];
-        for (x, i) in xs.into_iter().zip(inner.iter_mut()) {
-            *i = Some(x);
-        }
-        Self { inner }
+        Self(xs.into_iter().take(N).collect())
     }
 
+    // Panics if there are already `N` values.
     pub fn push(&mut self, x: T) {
-        for q in self.inner.iter_mut() {
-            if q.is_none() {
-                *q = Some(x);
-                break;
-            }
+        if self.0.try_push(x).is_err() {
+            panic!("AtMost holds at most {N} values");
         }
     }
+
+    pub fn into_inner(self) -> SmallVec<T, N> {
+        self.0
+    }
+}
+
+impl<T, const N: usize> Deref for AtMost<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for AtMost<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
 }
 
 impl<T, const N: usize> IntoIterator for AtMost<T, N> {
     type Item = T;
-    type IntoIter = Flatten<IntoIter<Option<T>, N>>;
+    type IntoIter = small_vec::IntoIter<T, N>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter().flatten()
+        self.0.into_iter()
     }
 }
 
 impl<'a, T, const N: usize> IntoIterator for &'a AtMost<T, N> {
     type Item = &'a T;
-    type IntoIter = Flatten<Iter<'a, Option<T>>>;
+    type IntoIter = Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.iter().flatten()
+        self.0.iter()
+    }
+}
+
+// Unordered pairs of distinct indices `(i, j)` with `i < j < n`, in
+// lexicographic order.
+#[derive(Debug, Clone)]
+pub struct IndexPairs {
+    n: usize,
+    i: usize,
+    j: usize,
+}
+
+impl IndexPairs {
+    pub fn new(n: usize) -> Self {
+        Self { n, i: 0, j: 1 }
     }
 }
 
+impl Iterator for IndexPairs {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.j >= self.n {
+            return None;
+        }
+        let result = (self.i, self.j);
+        self.j += 1;
+        if self.j == self.n {
+            self.i += 1;
+            self.j = self.i + 1;
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.j >= self.n {
+            return (0, Some(0));
+        }
+        // The rest of the current row, then all the later rows.
+        let rest = self.n - self.i - 1;
+        let len = (self.n - self.j) + rest * (rest - 1) / 2;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for IndexPairs {}
+
+// Unordered pairs of distinct elements of a slice, by reference.
+pub fn pairs<T>(xs: &[T]) -> impl ExactSizeIterator<Item = (&T, &T)> {
+    IndexPairs::new(xs.len()).map(move |(i, j)| (&xs[i], &xs[j]))
+}
+
+// Increasing sequences of `k` indices below `n`, in lexicographic order.
+// As an `Iterator` each combination is a new `Vec`; `next_indices` lends
+// them out as slices instead, like `Odometer`, so visiting does not allocate.
+#[derive(Debug, Clone)]
+pub struct IndexCombinations {
+    n: usize,
+    indices: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl IndexCombinations {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self {
+            n,
+            indices: (0..k).collect(),
+            started: false,
+            done: k > n,
+        }
+    }
+
+    pub fn next_indices(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            return Some(&self.indices);
+        }
+        let k = self.indices.len();
+        // Find the last index which can still move right, bump it and reset
+        // all the following indices to be consecutive after it.
+        let i = (0..k).rev().find(|&i| self.indices[i] < self.n - k + i);
+        let Some(i) = i else {
+            self.done = true;
+            return None;
+        };
+        self.indices[i] += 1;
+        for j in (i + 1)..k {
+            self.indices[j] = self.indices[j - 1] + 1;
+        }
+        Some(&self.indices)
+    }
+}
+
+impl Iterator for IndexCombinations {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_indices().map(<[usize]>::to_vec)
+    }
+}
+
+// Subsets of `k` elements of a slice, by reference and in slice order.
+// Allocates a new `Vec` per subset; use `IndexCombinations::next_indices` to avoid that.
+pub fn combinations<T>(xs: &[T], k: usize) -> impl Iterator<Item = Vec<&T>> {
+    let mut indices = IndexCombinations::new(xs.len(), k);
+    std::iter::from_fn(move || Some(indices.next_indices()?.iter().map(|i| &xs[*i]).collect()))
+}
+
+// All orderings of the indices `0..n`, generated with Heap's algorithm so
+// that consecutive permutations differ by a single swap. As with
+// `IndexCombinations`, `next_indices` visits them without allocating.
+#[derive(Debug, Clone)]
+pub struct IndexPermutations {
+    indices: Vec<usize>,
+    // Loop counters of the recursive formulation.
+    counters: Vec<usize>,
+    i: usize,
+    started: bool,
+}
+
+impl IndexPermutations {
+    pub fn new(n: usize) -> Self {
+        Self {
+            indices: (0..n).collect(),
+            counters: vec![0; n],
+            i: 1,
+            started: false,
+        }
+    }
+
+    pub fn next_indices(&mut self) -> Option<&[usize]> {
+        if !self.started {
+            self.started = true;
+            return Some(&self.indices);
+        }
+        while self.i < self.indices.len() {
+            let i = self.i;
+            if self.counters[i] < i {
+                if i.is_multiple_of(2) {
+                    self.indices.swap(0, i);
+                } else {
+                    self.indices.swap(self.counters[i], i);
+                }
+                self.counters[i] += 1;
+                self.i = 1;
+                return Some(&self.indices);
+            }
+            self.counters[i] = 0;
+            self.i += 1;
+        }
+        None
+    }
+}
+
+impl Iterator for IndexPermutations {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_indices().map(<[usize]>::to_vec)
+    }
+}
+
+// All orderings of the elements of a slice, by reference.
+// Allocates a new `Vec` per ordering; use `IndexPermutations::next_indices` to avoid that.
+pub fn permutations<T>(xs: &[T]) -> impl Iterator<Item = Vec<&T>> {
+    let mut indices = IndexPermutations::new(xs.len());
+    std::iter::from_fn(move || Some(indices.next_indices()?.iter().map(|i| &xs[*i]).collect()))
+}
+
+// All subsets of `0..n` as bitmasks, from the empty set up to the full set.
+// `next_indices` lends out the members of each subset instead of its mask.
+// Panics if `n >= 64`.
+#[derive(Debug, Clone)]
+pub struct Subsets {
+    next: u64,
+    end: u64,
+    indices: Vec<usize>,
+}
+
+impl Subsets {
+    pub fn new(n: u32) -> Self {
+        assert!(n < u64::BITS, "Too many elements for a bitmask");
+        Self {
+            next: 0,
+            end: 1 << n,
+            indices: Vec::with_capacity(n as usize),
+        }
+    }
+
+    pub fn next_indices(&mut self) -> Option<&[usize]> {
+        let mask = Iterator::next(self)?;
+        self.indices.clear();
+        self.indices
+            .extend((0..u64::BITS as usize).filter(|i| mask & (1 << i) != 0));
+        Some(&self.indices)
+    }
+}
+
+impl Iterator for Subsets {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+        self.next += 1;
+        Some(self.next - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Subsets {}
+
+// All subsets of a slice, by reference and in slice order.
+// Allocates a new `Vec` per subset; use `Subsets::next_indices` to avoid that.
+pub fn subsets<T>(xs: &[T]) -> impl Iterator<Item = Vec<&T>> {
+    let mut indices = Subsets::new(xs.len() as u32);
+    std::iter::from_fn(move || Some(indices.next_indices()?.iter().map(|i| &xs[*i]).collect()))
+}
+
 #[test]
 fn test_cartesian_product() {
     let xs = vec![0u8, 1, 2, 3];
@@ -290,3 +569,141 @@ fn test_dyn_cartesian_product() {
     let product: Vec<Vec<u32>> = DynCartesianProduct::new(Vec::new()).unwrap().collect();
     assert_eq!(product, vec![vec![]],);
 }
+
+#[test]
+fn test_pairs() {
+    let indices: Vec<(usize, usize)> = IndexPairs::new(4).collect();
+    assert_eq!(
+        indices,
+        vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+    );
+    assert_eq!(IndexPairs::new(0).count(), 0);
+    assert_eq!(IndexPairs::new(1).count(), 0);
+
+    let mut iter = IndexPairs::new(10);
+    assert_eq!(iter.len(), 45);
+    iter.nth(11);
+    assert_eq!(iter.len(), 33);
+
+    let words = ["a", "b", "c"];
+    let joined: Vec<String> = pairs(&words).map(|(x, y)| format!("{x}{y}")).collect();
+    assert_eq!(joined, vec!["ab", "ac", "bc"]);
+}
+
+#[test]
+fn test_combinations() {
+    let combs: Vec<Vec<usize>> = IndexCombinations::new(4, 2).collect();
+    assert_eq!(
+        combs,
+        IndexPairs::new(4)
+            .map(|(i, j)| vec![i, j])
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(IndexCombinations::new(5, 3).count(), 10);
+    assert_eq!(
+        IndexCombinations::new(3, 0).collect::<Vec<_>>(),
+        vec![vec![]]
+    );
+    assert_eq!(IndexCombinations::new(2, 3).count(), 0);
+
+    let xs = [10, 20, 30];
+    let combs: Vec<Vec<&i32>> = combinations(&xs, 2).collect();
+    assert_eq!(combs, vec![vec![&10, &20], vec![&10, &30], vec![&20, &30]]);
+
+    let mut lent = IndexCombinations::new(5, 3);
+    let mut count = 0;
+    while let Some(c) = lent.next_indices() {
+        assert!(c.windows(2).all(|w| w[0] < w[1]));
+        count += 1;
+    }
+    assert_eq!(count, 10);
+    assert_eq!(lent.next_indices(), None);
+}
+
+#[test]
+fn test_permutations() {
+    for n in 0..6 {
+        let mut perms: Vec<Vec<usize>> = IndexPermutations::new(n).collect();
+        // Consecutive permutations differ by one swap.
+        for w in perms.windows(2) {
+            let changed = w[0].iter().zip(&w[1]).filter(|(a, b)| a != b).count();
+            assert_eq!(changed, 2);
+        }
+        let count = perms.len();
+        perms.sort_unstable();
+        perms.dedup();
+        assert_eq!(perms.len(), count);
+        assert_eq!(count, (1..=n).product::<usize>());
+    }
+
+    let xs = ['x', 'y'];
+    let perms: Vec<Vec<&char>> = permutations(&xs).collect();
+    assert_eq!(perms, vec![vec![&'x', &'y'], vec![&'y', &'x']]);
+
+    let mut lent = IndexPermutations::new(4);
+    let mut count = 0;
+    while let Some(p) = lent.next_indices() {
+        assert_eq!(p.iter().sum::<usize>(), 6);
+        count += 1;
+    }
+    assert_eq!(count, 24);
+}
+
+#[test]
+fn test_subsets() {
+    assert_eq!(
+        Subsets::new(3).collect::<Vec<_>>(),
+        (0..8).collect::<Vec<_>>()
+    );
+    assert_eq!(Subsets::new(0).collect::<Vec<_>>(), vec![0]);
+    let xs = [1, 2, 3];
+    let sums: Vec<i32> = subsets(&xs).map(|s| s.into_iter().sum()).collect();
+    assert_eq!(sums, vec![0, 1, 2, 3, 3, 4, 5, 6]);
+
+    let mut lent = Subsets::new(3);
+    let mut members = Vec::new();
+    while let Some(s) = lent.next_indices() {
+        members.push(s.to_vec());
+    }
+    assert_eq!(members[5], vec![0, 2]);
+    assert_eq!(members[7], vec![0, 1, 2]);
+    assert_eq!(members.len(), 8);
+}
+
+#[test]
+fn test_odometer() {
+    let mut odometer = Odometer::new(vec![2, 3]);
+    assert_eq!(odometer.len(), Some(6));
+    let mut points = Vec::new();
+    while let Some(point) = odometer.next() {
+        points.push(point.to_vec());
+    }
+    assert_eq!(points.len(), 6);
+    assert_eq!(points[4], vec![1, 1]);
+    assert_eq!(odometer.current(), None);
+
+    assert!(Odometer::new(vec![3, 0]).next().is_none());
+    assert_eq!(Odometer::new(vec![u32::MAX; 5]).len(), None);
+
+    // Prune every point whose first digit is 1, and stop once the first
+    // digit reaches 3.
+    let mut odometer = Odometer::new(vec![4, 2, 2]);
+    let mut points = Vec::new();
+    while let Some(point) = odometer.next() {
+        match point[0] {
+            1 => odometer.skip_prefix(1),
+            3 => break,
+            _ => points.push(point.to_vec()),
+        }
+    }
+    let expected: Vec<Vec<u32>> = DynCartesianProduct::new(vec![4, 2, 2])
+        .unwrap()
+        .filter(|p| p[0] == 0 || p[0] == 2)
+        .collect();
+    assert_eq!(points, expected);
+
+    let mut odometer = Odometer::new(vec![3, 3]);
+    odometer.next();
+    odometer.skip_prefix(0);
+    assert_eq!(odometer.next(), None);
+}
@@ -0,0 +1,262 @@
+use crate::{
+    iter::pairs,
+    rational::Rational,
+    vector::{Vec2, Vec3},
+};
+
+// A point moving in a straight line at constant velocity, so that its position
+// at time `t` is `position + t * velocity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Particle<V> {
+    pub position: V,
+    pub velocity: V,
+}
+
+pub type Particle2 = Particle<Vec2<i128>>;
+pub type Particle3 = Particle<Vec3<i128>>;
+
+impl<V> Particle<V> {
+    pub fn new(position: V, velocity: V) -> Self {
+        Self { position, velocity }
+    }
+}
+
+// How the paths (infinite lines) of two particles relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathIntersection {
+    // The paths cross at a single point, which the first particle reaches at
+    // time `t` and the second at time `s`. Negative times are in the past.
+    Crossing {
+        t: Rational<i128>,
+        s: Rational<i128>,
+    },
+    // The paths are parallel and never meet.
+    Parallel,
+    // The paths are the same line.
+    Collinear,
+    // The paths are not parallel but never meet (3D only).
+    Skew,
+}
+
+impl PathIntersection {
+    // Whether the paths cross at a point both particles reach at non-negative
+    // times, i.e. the forward rays from the current positions intersect.
+    pub fn is_future_crossing(&self) -> bool {
+        match self {
+            Self::Crossing { t, s } => *t >= Rational::ZERO && *s >= Rational::ZERO,
+            _ => false,
+        }
+    }
+}
+
+impl Particle2 {
+    pub fn position_at(&self, t: Rational<i128>) -> Vec2<Rational<i128>> {
+        self.position.map(Rational::integer) + self.velocity.map(Rational::integer).map(|v| v * t)
+    }
+
+    pub fn path_intersection(&self, other: &Self) -> PathIntersection {
+        // Solve t * v1 - s * v2 = p2 - p1 with Cramer's rule.
+        let d = other.position - self.position;
+        let det = self.velocity.cross(&other.velocity);
+        if det == 0 {
+            return if d.cross(&self.velocity) == 0 {
+                PathIntersection::Collinear
+            } else {
+                PathIntersection::Parallel
+            };
+        }
+        PathIntersection::Crossing {
+            t: Rational::new(d.cross(&other.velocity), det),
+            s: Rational::new(d.cross(&self.velocity), det),
+        }
+    }
+}
+
+impl Particle3 {
+    pub fn position_at(&self, t: Rational<i128>) -> Vec3<Rational<i128>> {
+        self.position.map(Rational::integer) + self.velocity.map(Rational::integer).map(|v| v * t)
+    }
+
+    pub fn path_intersection(&self, other: &Self) -> PathIntersection {
+        let d = other.position - self.position;
+        let normal = self.velocity.cross(&other.velocity);
+        if normal == Vec3::default() {
+            return if d.cross(&self.velocity) == Vec3::default() {
+                PathIntersection::Collinear
+            } else {
+                PathIntersection::Parallel
+            };
+        }
+        // Lines which cross lie in a common plane.
+        if d.dot(&normal) != 0 {
+            return PathIntersection::Skew;
+        }
+        let det = normal.squared_norm();
+        PathIntersection::Crossing {
+            t: Rational::new(d.cross(&other.velocity).dot(&normal), det),
+            s: Rational::new(d.cross(&self.velocity).dot(&normal), det),
+        }
+    }
+
+    // Whether `self` and `other` are at the same place at some time `t >= 0`.
+    pub fn collides_with(&self, other: &Self) -> bool {
+        // Need position - other.position = t * (other.velocity - velocity)
+        let d = self.position - other.position;
+        let w = other.velocity - self.velocity;
+        if w == Vec3::default() {
+            return d == Vec3::default();
+        }
+        d.cross(&w) == Vec3::default() && d.dot(&w) >= 0
+    }
+}
+
+// Find the particle with integer position and velocity which collides with every
+// one of the given particles, or `None` if there is no such particle.
+// Any three particles in general position determine the answer, which is then
+// checked against all the others.
+pub fn intercept_all(particles: &[Particle3]) -> Option<Particle3> {
+    let (first, rest) = particles.split_first()?;
+    let candidate = pairs(rest).find_map(|(a, b)| intercept_three(first, a, b))?;
+    // The candidate is only valid if it is consistent with every particle.
+    particles
+        .iter()
+        .all(|p| candidate.collides_with(p))
+        .then_some(candidate)
+}
+
+// Solve for the path through three particles in the rest frame of `base`, where
+// `base` is stationary at the origin. Returns `None` if the particles are degenerate
+// (e.g. their relative motion is parallel) or the answer is not integral.
+fn intercept_three(base: &Particle3, a: &Particle3, b: &Particle3) -> Option<Particle3> {
+    let p1 = a.position - base.position;
+    let p2 = b.position - base.position;
+    let v1 = a.velocity - base.velocity;
+    let v2 = b.velocity - base.velocity;
+
+    // The path passes through the origin and a point on each of the other two
+    // relative paths, so it lies in the plane through the origin and each path.
+    let n = p1.cross(&p2);
+    let t1_denominator = v1.cross(&p2).dot(&v2);
+    let t2_denominator = p1.cross(&v2).dot(&v1);
+    if t1_denominator == 0 || t2_denominator == 0 {
+        return None;
+    }
+    let t1 = Rational::new(-n.dot(&v2), t1_denominator);
+    let t2 = Rational::new(-n.dot(&v1), t2_denominator);
+    if t1 == t2 {
+        return None;
+    }
+
+    let c1 = a.position_at(t1);
+    let c2 = b.position_at(t2);
+    let dt = t2 - t1;
+    let velocity = (c2 - c1).map(|x| x / dt);
+    let position = c1 - velocity.map(|x| x * t1);
+
+    let to_integers = |v: Vec3<Rational<i128>>| {
+        Some(Vec3::new(
+            v.x.to_integer()?,
+            v.y.to_integer()?,
+            v.z.to_integer()?,
+        ))
+    };
+    Some(Particle::new(
+        to_integers(position)?,
+        to_integers(velocity)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::random::XorShift};
+
+    fn particle3(p: [i128; 3], v: [i128; 3]) -> Particle3 {
+        Particle::new(p.into(), v.into())
+    }
+
+    #[test]
+    fn test_path_intersection_2d() {
+        let a = Particle::new(Vec2::new(19, 13), Vec2::new(-2, 1));
+        let b = Particle::new(Vec2::new(18, 19), Vec2::new(-1, -1));
+        let PathIntersection::Crossing { t, s } = a.path_intersection(&b) else {
+            panic!("Paths cross");
+        };
+        assert_eq!(a.position_at(t), b.position_at(s));
+        assert_eq!(
+            a.position_at(t),
+            Vec2::new(Rational::new(43, 3), Rational::new(46, 3))
+        );
+        assert!(a.path_intersection(&b).is_future_crossing());
+
+        // Crossing in the past for one of them
+        let c = Particle::new(Vec2::new(20, 19), Vec2::new(1, -5));
+        assert!(!a.path_intersection(&c).is_future_crossing());
+
+        let d = Particle::new(Vec2::new(20, 25), Vec2::new(-2, -2));
+        assert_eq!(b.path_intersection(&d), PathIntersection::Parallel);
+        let e = Particle::new(Vec2::new(15, 15), Vec2::new(4, -2));
+        assert_eq!(a.path_intersection(&e), PathIntersection::Collinear);
+    }
+
+    #[test]
+    fn test_path_intersection_3d() {
+        let a = particle3([0, 0, 0], [1, 1, 1]);
+        let b = particle3([4, 0, 0], [-1, 1, 1]);
+        assert_eq!(
+            a.path_intersection(&b),
+            PathIntersection::Crossing {
+                t: Rational::integer(2),
+                s: Rational::integer(2)
+            }
+        );
+        let c = particle3([4, 0, 1], [-1, 1, 1]);
+        assert_eq!(a.path_intersection(&c), PathIntersection::Skew);
+        let d = particle3([1, 0, 0], [2, 2, 2]);
+        assert_eq!(a.path_intersection(&d), PathIntersection::Parallel);
+        let e = particle3([-3, -3, -3], [2, 2, 2]);
+        assert_eq!(a.path_intersection(&e), PathIntersection::Collinear);
+    }
+
+    #[test]
+    fn test_intercept_all() {
+        let stones = [
+            particle3([19, 13, 30], [-2, 1, -2]),
+            particle3([18, 19, 22], [-1, -1, -2]),
+            particle3([20, 25, 34], [-2, -2, -4]),
+            particle3([12, 31, 28], [-1, -2, -1]),
+            particle3([20, 19, 15], [1, -5, -3]),
+        ];
+        assert_eq!(
+            intercept_all(&stones),
+            Some(particle3([24, 13, 10], [-3, 1, 2]))
+        );
+
+        // A stone the rock cannot hit makes the problem infeasible.
+        let mut stones = stones.to_vec();
+        stones.push(particle3([0, 0, 0], [0, 0, 1]));
+        assert_eq!(intercept_all(&stones), None);
+    }
+
+    #[test]
+    fn test_intercept_all_random() {
+        let mut rng = XorShift::new(24);
+        let mut coordinate = |range: u64| rng.below(2 * range + 1) as i128 - range as i128;
+        for _ in 0..20 {
+            let rock = Particle::new(
+                Vec3::new(coordinate(1000), coordinate(1000), coordinate(1000)),
+                Vec3::new(coordinate(10), coordinate(10), coordinate(10)),
+            );
+            let stones: Vec<Particle3> = (0..6)
+                .map(|_| {
+                    let t = coordinate(100).abs() + 1;
+                    let velocity = Vec3::new(coordinate(10), coordinate(10), coordinate(10));
+                    // Meets the rock at time `t`.
+                    let meeting = rock.position + rock.velocity * t;
+                    Particle::new(meeting - velocity * t, velocity)
+                })
+                .collect();
+            assert!(stones.iter().all(|s| rock.collides_with(s)));
+            assert_eq!(intercept_all(&stones), Some(rock));
+        }
+    }
+}
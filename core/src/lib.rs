@@ -3,12 +3,32 @@ use {
     std::{collections::HashMap, fmt::Debug, hash::Hash, ops::Sub},
 };
 
+pub mod bigint;
+pub mod bitset;
+pub mod geometry;
 pub mod graph;
 pub mod grid;
+pub mod hyperbox;
+pub mod ilp;
+pub mod intern;
+pub mod intervals;
 pub mod iter;
+pub mod kinematics;
 pub mod linked_list;
 pub mod matrix;
+pub mod memo;
 pub mod min_heap;
+pub mod num;
+pub mod number_theory;
+pub mod parse;
+pub mod pattern;
+pub mod poly;
+pub mod priority_queue;
+pub mod random;
+pub mod rational;
+pub mod scan;
+pub mod small_vec;
+pub mod vector;
 
 pub trait Solution<'a> {
     type Input: Clone + 'a;
@@ -75,17 +95,7 @@ where
 }
 
 pub fn isqrt(x: u64) -> u64 {
-    let mut lower = 0;
-    let mut upper = x + 1;
-    while lower != upper - 1 {
-        let middle = (lower + upper) / 2;
-        if middle * middle <= x {
-            lower = middle;
-        } else {
-            upper = middle;
-        }
-    }
-    lower
+    num::isqrt(x)
 }
 
 // Returns a pair where the smaller element is first
@@ -120,18 +130,12 @@ pub fn n_digits(x: u64) -> u32 {
 }
 
 pub fn gcd(x: u64, y: u64) -> u64 {
-    let (mut a, mut b) = if x < y { (y, x) } else { (x, y) };
-    let mut c = a % b;
-    while c != 0 {
-        a = b;
-        b = c;
-        c = a % b;
-    }
-    b
+    num::gcd(x, y)
 }
 
+// Panics on overflow, see `num::checked_lcm` for a non-panicking version.
 pub fn lcm(x: u64, y: u64) -> u64 {
-    x * y / gcd(x, y)
+    num::lcm(x, y)
 }
 
 pub fn id<T>(t: T) -> T {
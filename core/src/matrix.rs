@@ -1,4 +1,7 @@
-use crate::gcd;
+use crate::{
+    gcd,
+    rational::{Rational, SignedInteger},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IntegerMatrix {
@@ -121,6 +124,239 @@ impl IntegerMatrix {
     }
 }
 
+// Dense matrix, mostly used with `Rational` entries for exact linear algebra.
+// `Rational` arithmetic is not checked for overflow, so numerators and
+// denominators must stay within `T` during elimination. Use a wider `T`
+// (e.g. `i128`) when the entries are large.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    pub rows: Vec<Vec<T>>,
+}
+
+// Result of solving `Ax = b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearSolution<T> {
+    Unique(Vec<T>),
+    // Every solution has the form `particular + sum(t_i * directions[i])` where
+    // `t_i` is the value of the free variable `free_variables[i]`.
+    Parametric {
+        particular: Vec<T>,
+        free_variables: Vec<usize>,
+        directions: Vec<Vec<T>>,
+    },
+    Inconsistent,
+}
+
+impl<T> Matrix<T> {
+    pub fn n_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.rows.first().map(|r| r.len()).unwrap_or_default()
+    }
+}
+
+impl<T: SignedInteger> From<&IntegerMatrix> for Matrix<Rational<T>>
+where
+    T: From<i64>,
+{
+    fn from(value: &IntegerMatrix) -> Self {
+        let rows = value
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|x| Rational::integer((*x).into())).collect())
+            .collect();
+        Self { rows }
+    }
+}
+
+impl<T: SignedInteger> Matrix<Rational<T>> {
+    pub fn identity(n: usize) -> Self {
+        let mut rows = vec![vec![Rational::ZERO; n]; n];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = Rational::ONE;
+        }
+        Self { rows }
+    }
+
+    pub fn from_integers(rows: Vec<Vec<T>>) -> Self {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Rational::integer).collect())
+            .collect();
+        Self { rows }
+    }
+
+    // Put the matrix in reduced row echelon form, returning the pivot columns.
+    // Only the first `n_cols` columns are used for pivots, which allows
+    // reducing augmented matrices.
+    pub fn rref_columns(&mut self, n_cols: usize) -> Vec<usize> {
+        let n = self.n_rows();
+        let mut pivots = Vec::new();
+        let mut current_row = 0;
+        for current_col in 0..n_cols {
+            if current_row == n {
+                break;
+            }
+            let Some(pivot_row) = (current_row..n).find(|r| !self.rows[*r][current_col].is_zero())
+            else {
+                continue;
+            };
+            self.rows.swap(pivot_row, current_row);
+
+            let leading = self.rows[current_row][current_col];
+            for x in self.rows[current_row].iter_mut() {
+                *x /= leading;
+            }
+
+            for r in 0..n {
+                let factor = self.rows[r][current_col];
+                if r == current_row || factor.is_zero() {
+                    continue;
+                }
+                for c in 0..self.rows[r].len() {
+                    let delta = factor * self.rows[current_row][c];
+                    self.rows[r][c] -= delta;
+                }
+            }
+
+            pivots.push(current_col);
+            current_row += 1;
+        }
+        pivots
+    }
+
+    pub fn rref(&mut self) -> Vec<usize> {
+        self.rref_columns(self.n_cols())
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().rref().len()
+    }
+
+    // Returns `None` if the matrix is not square.
+    pub fn determinant(&self) -> Option<Rational<T>> {
+        let n = self.n_rows();
+        if n != self.n_cols() {
+            return None;
+        }
+
+        // Gaussian elimination to upper triangular form,
+        // tracking the effect of row swaps on the sign.
+        let mut rows = self.rows.clone();
+        let mut det = Rational::ONE;
+        for col in 0..n {
+            let Some(pivot_row) = (col..n).find(|r| !rows[*r][col].is_zero()) else {
+                return Some(Rational::ZERO);
+            };
+            if pivot_row != col {
+                rows.swap(pivot_row, col);
+                det = -det;
+            }
+            let pivot = rows[col][col];
+            det *= pivot;
+            let (upper, lower) = rows.split_at_mut(col + 1);
+            for row in lower {
+                let factor = row[col] / pivot;
+                if factor.is_zero() {
+                    continue;
+                }
+                for (x, y) in row[col..].iter_mut().zip(&upper[col][col..]) {
+                    *x -= factor * *y;
+                }
+            }
+        }
+        Some(det)
+    }
+
+    // Returns `None` if the matrix is not square or is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let n = self.n_rows();
+        if n != self.n_cols() {
+            return None;
+        }
+
+        let identity = Self::identity(n);
+        let rows = self
+            .rows
+            .iter()
+            .zip(identity.rows)
+            .map(|(row, id_row)| row.iter().copied().chain(id_row).collect())
+            .collect();
+        let mut augmented = Self { rows };
+        if augmented.rref_columns(n).len() < n {
+            return None;
+        }
+        let rows = augmented
+            .rows
+            .into_iter()
+            .map(|row| row[n..].to_vec())
+            .collect();
+        Some(Self { rows })
+    }
+
+    pub fn mul_vec(&self, x: &[Rational<T>]) -> Vec<Rational<T>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(x)
+                    .fold(Rational::ZERO, |acc, (a, b)| acc + *a * *b)
+            })
+            .collect()
+    }
+
+    // Solve `Ax = b` where `A` is this matrix.
+    // Panics if `b` does not have one entry per row.
+    pub fn solve(&self, b: &[Rational<T>]) -> LinearSolution<Rational<T>> {
+        assert_eq!(b.len(), self.n_rows(), "One right hand side per row");
+        let m = self.n_cols();
+        let rows = self
+            .rows
+            .iter()
+            .zip(b)
+            .map(|(row, b)| row.iter().copied().chain(std::iter::once(*b)).collect())
+            .collect();
+        let mut augmented = Self { rows };
+        let pivots = augmented.rref_columns(m);
+
+        // A row of zeros equal to something non-zero means there is no solution.
+        if augmented.rows[pivots.len()..]
+            .iter()
+            .any(|row| !row[m].is_zero())
+        {
+            return LinearSolution::Inconsistent;
+        }
+
+        let mut particular = vec![Rational::ZERO; m];
+        for (row, col) in pivots.iter().enumerate() {
+            particular[*col] = augmented.rows[row][m];
+        }
+        if pivots.len() == m {
+            return LinearSolution::Unique(particular);
+        }
+
+        let free_variables: Vec<usize> = (0..m).filter(|c| !pivots.contains(c)).collect();
+        let directions = free_variables
+            .iter()
+            .map(|free| {
+                let mut direction = vec![Rational::ZERO; m];
+                direction[*free] = Rational::ONE;
+                for (row, col) in pivots.iter().enumerate() {
+                    direction[*col] = -augmented.rows[row][*free];
+                }
+                direction
+            })
+            .collect();
+        LinearSolution::Parametric {
+            particular,
+            free_variables,
+            directions,
+        }
+    }
+}
+
 #[test]
 fn test_row_reduce() {
     let mut matrix = IntegerMatrix {
@@ -153,3 +389,59 @@ fn test_row_reduce() {
         assert_eq!(row.last().unwrap(), answer);
     }
 }
+
+#[test]
+fn test_rational_solve() {
+    let r = |x: i64| Rational::integer(x);
+    let matrix = Matrix::from_integers(vec![vec![2_i64, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]]);
+    assert_eq!(matrix.determinant(), Some(r(-1)));
+    assert_eq!(matrix.rank(), 3);
+    assert_eq!(
+        matrix.solve(&[r(8), r(-11), r(-3)]),
+        LinearSolution::Unique(vec![r(2), r(3), r(-1)])
+    );
+
+    let inverse = matrix.inverse().unwrap();
+    assert_eq!(
+        inverse.mul_vec(&[r(8), r(-11), r(-3)]),
+        vec![r(2), r(3), r(-1)]
+    );
+
+    let singular = Matrix::from_integers(vec![vec![1_i64, 2], vec![2, 4]]);
+    assert_eq!(singular.determinant(), Some(r(0)));
+    assert_eq!(singular.inverse(), None);
+    assert_eq!(singular.solve(&[r(1), r(3)]), LinearSolution::Inconsistent);
+
+    let LinearSolution::Parametric {
+        particular,
+        free_variables,
+        directions,
+    } = singular.solve(&[r(3), r(6)])
+    else {
+        panic!("Expected a family of solutions");
+    };
+    assert_eq!(free_variables, vec![1]);
+    for t in -3..3 {
+        let x: Vec<Rational<i64>> = particular
+            .iter()
+            .zip(&directions[0])
+            .map(|(p, d)| *p + r(t) * *d)
+            .collect();
+        assert_eq!(singular.mul_vec(&x), vec![r(3), r(6)]);
+    }
+
+    let halves = Matrix::from_integers(vec![vec![2_i128, 0], vec![0, 4]]);
+    assert_eq!(
+        halves.solve(&[Rational::integer(1), Rational::integer(1)]),
+        LinearSolution::Unique(vec![Rational::new(1, 2), Rational::new(1, 4)])
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_solve_wrong_length() {
+    let matrix: Matrix<Rational<i64>> = Matrix::from(&IntegerMatrix {
+        rows: vec![vec![1, 2], vec![3, 4]],
+    });
+    matrix.solve(&[Rational::ONE]);
+}
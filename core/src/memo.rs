@@ -0,0 +1,255 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::Hash,
+    marker::PhantomData,
+};
+
+// Storage for the results of a memoized function.
+pub trait Cache<K, V> {
+    // Takes `&mut self` so caches can track how recently keys were used.
+    fn get(&mut self, key: &K) -> Option<V>;
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> for HashMap<K, V> {
+    fn get(&mut self, key: &K) -> Option<V> {
+        HashMap::get(self, key).cloned()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+// Cache for keys which map to a bounded range of integers, e.g. a position in
+// a string or `(row, col)` in a grid. Panics if `index` maps a key out of range.
+#[derive(Debug, Clone)]
+pub struct ArrayCache<V, F> {
+    values: Vec<Option<V>>,
+    index: F,
+}
+
+impl<V: Clone, F> ArrayCache<V, F> {
+    // `index` must map every key to `0..len`.
+    pub fn new(len: usize, index: F) -> Self {
+        Self {
+            values: vec![None; len],
+            index,
+        }
+    }
+}
+
+impl<K, V: Clone, F: Fn(&K) -> usize> Cache<K, V> for ArrayCache<V, F> {
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.values[(self.index)(key)].clone()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.values[(self.index)(&key)] = Some(value);
+    }
+}
+
+// Cache holding at most `capacity` values, evicting the least recently used.
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    // Each value with the time it was last used.
+    values: HashMap<K, (V, u64)>,
+    by_time: BTreeMap<u64, K>,
+    time: u64,
+}
+
+impl<K, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LRU cache needs room for a value");
+        Self {
+            capacity,
+            values: HashMap::new(),
+            by_time: BTreeMap::new(),
+            time: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Cache<K, V> for LruCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<V> {
+        let (value, used) = self.values.get_mut(key)?;
+        self.by_time.remove(used);
+        self.time += 1;
+        *used = self.time;
+        self.by_time.insert(self.time, key.clone());
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.time += 1;
+        if let Some((_, used)) = self.values.get(&key) {
+            self.by_time.remove(used);
+        } else if self.values.len() == self.capacity {
+            let (_, oldest) = self.by_time.pop_first().expect("Cache is full");
+            self.values.remove(&oldest);
+        }
+        self.by_time.insert(self.time, key.clone());
+        self.values.insert(key, (value, self.time));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Stats {
+    // Fraction of lookups answered by the cache, 0 if there were none.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            100.0 * self.hit_rate()
+        )
+    }
+}
+
+// Memoizes a recursive function. The function takes the memo as its first
+// argument and makes its recursive calls through `call`, e.g.
+//
+//     fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//         if n < 2 {
+//             return n;
+//         }
+//         memo.call(n - 1, fib) + memo.call(n - 2, fib)
+//     }
+//     let answer = Memo::new().call(90, fib);
+//
+// Extra arguments can be passed with a closure:
+// `memo.call(key, |memo, key| f(memo, key, context))`.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, C = HashMap<K, V>> {
+    cache: C,
+    stats: Stats,
+    marker: PhantomData<fn(K) -> V>,
+}
+
+impl<K, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_cache(HashMap::new())
+    }
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, C> Memo<K, V, C> {
+    pub fn with_cache(cache: C) -> Self {
+        Self {
+            cache,
+            stats: Stats::default(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn cache(&self) -> &C {
+        &self.cache
+    }
+}
+
+impl<K: Clone, V: Clone, C: Cache<K, V>> Memo<K, V, C> {
+    // The cached value for `key`, or else `f(self, key)`, which is then cached.
+    pub fn call<F: FnOnce(&mut Self, K) -> V>(&mut self, key: K, f: F) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value;
+        }
+        self.stats.misses += 1;
+        let value = f(self, key.clone());
+        self.cache.insert(key, value.clone());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib<C: Cache<u64, u64>>(memo: &mut Memo<u64, u64, C>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.call(n - 1, fib) + memo.call(n - 2, fib)
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(memo.call(90, fib), 2_880_067_194_370_816_120);
+        // Each value is computed once and looked up once more.
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 88,
+                misses: 91
+            }
+        );
+        assert_eq!(memo.call(90, fib), 2_880_067_194_370_816_120);
+        assert_eq!(memo.stats().hits, 89);
+
+        let mut dense = Memo::with_cache(ArrayCache::new(91, |n: &u64| *n as usize));
+        assert_eq!(dense.call(90, fib), 2_880_067_194_370_816_120);
+        assert_eq!(
+            dense.stats().to_string(),
+            "88 hits, 91 misses (49.2% hit rate)"
+        );
+
+        // Fibonacci only ever needs the last two values.
+        let mut lru = Memo::with_cache(LruCache::new(3));
+        assert_eq!(lru.call(90, fib), 2_880_067_194_370_816_120);
+        assert_eq!(lru.cache().len(), 3);
+    }
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        cache.insert('a', 1);
+        cache.insert('b', 2);
+        assert_eq!(cache.get(&'a'), Some(1));
+        // `b` is now the least recently used.
+        cache.insert('c', 3);
+        assert_eq!(cache.get(&'b'), None);
+        assert_eq!(cache.get(&'a'), Some(1));
+        cache.insert('c', 4);
+        cache.insert('d', 5);
+        assert_eq!(cache.get(&'a'), None);
+        assert_eq!(cache.get(&'c'), Some(4));
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -27,6 +27,18 @@ impl<T: Ord> MinHeap<T> {
     pub fn pop(&mut self) -> Option<T> {
         self.inner.pop().map(|x| x.0)
     }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.inner.peek().map(|x| &x.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
 }
 
 impl<T: Ord> FromIterator<T> for MinHeap<T> {
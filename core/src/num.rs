@@ -0,0 +1,143 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+// Primitive integer types (signed or unsigned) supported by the generic helpers below.
+pub trait Integer:
+    Copy
+    + Ord
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    // Identity for unsigned types.
+    fn abs(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($abs:expr; $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn abs(self) -> Self {
+                    $abs(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(|x| x; u8, u16, u32, u64, u128, usize);
+impl_integer!(|x: Self| x.abs(); i8, i16, i32, i64, i128, isize);
+
+// Greatest common divisor, always non-negative. `gcd(0, 0) = 0`.
+pub fn gcd<T: Integer>(x: T, y: T) -> T {
+    let (mut a, mut b) = (x.abs(), y.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// Returns `None` if the result does not fit in `T`.
+pub fn checked_lcm<T: Integer>(x: T, y: T) -> Option<T> {
+    if x == T::ZERO || y == T::ZERO {
+        return Some(T::ZERO);
+    }
+    // Divide before multiplying so overflow only happens if the answer is too large.
+    (x.abs() / gcd(x, y)).checked_mul(y.abs())
+}
+
+// Panics on overflow.
+pub fn lcm<T: Integer>(x: T, y: T) -> T {
+    checked_lcm(x, y).unwrap_or_else(|| panic!("lcm({x}, {y}) overflows"))
+}
+
+// Largest `r` such that `r * r <= x`. Returns `None` if `x` is negative.
+pub fn checked_isqrt<T: Integer>(x: T) -> Option<T> {
+    if x < T::ZERO {
+        return None;
+    }
+    // Invariant: the answer is in `[lower, upper]`.
+    let mut lower = T::ZERO;
+    let mut upper = x;
+    let two = T::ONE + T::ONE;
+    while lower < upper {
+        // Round the midpoint up so the loop always makes progress.
+        let gap = upper - lower;
+        let middle = lower + gap / two + gap % two;
+        match middle.checked_mul(middle) {
+            Some(square) if square <= x => lower = middle,
+            _ => upper = middle - T::ONE,
+        }
+    }
+    Some(lower)
+}
+
+// Panics if `x` is negative.
+pub fn isqrt<T: Integer>(x: T) -> T {
+    checked_isqrt(x).unwrap_or_else(|| panic!("Square root of negative number {x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(462_u128, 1071), 21);
+        assert_eq!(gcd(-462_i64, 1071), 21);
+        assert_eq!(gcd(0_u64, 5), 5);
+        assert_eq!(gcd(0_i32, 0), 0);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4_u64, 6), 12);
+        assert_eq!(lcm(-4_i128, 6), 12);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(
+            checked_lcm(u64::MAX as u128, (u64::MAX - 1) as u128),
+            Some((u64::MAX as u128) * ((u64::MAX - 1) as u128))
+        );
+        assert_eq!(checked_lcm(0_u8, 7), Some(0));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for x in 0_u64..1000 {
+            let r = isqrt(x);
+            assert!(r * r <= x && (r + 1) * (r + 1) > x);
+        }
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt(i128::MAX), 13_043_817_825_332_782_212);
+        assert_eq!(checked_isqrt(-1_i64), None);
+    }
+}
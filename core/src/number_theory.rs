@@ -0,0 +1,133 @@
+// Returns `(g, x, y)` such that `a * x + b * y = g = gcd(a, b)`, with `g >= 0`.
+// See https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+// Multiplicative inverse of `a` modulo `m`, in the range `[0, m)`.
+// Returns `None` if `a` and `m` are not coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+// Computes `base^exponent mod m` by repeated squaring.
+pub fn mod_pow(base: u64, mut exponent: u64, m: u64) -> u64 {
+    let m = m as u128;
+    let mut base = (base as u128) % m;
+    let mut result = 1 % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+// Generalized Chinese remainder theorem: find `x` such that `x = r_i (mod m_i)`
+// for every `(r_i, m_i)`. The moduli do not need to be coprime.
+// Returns `(x, lcm(m_i))` with `0 <= x < lcm(m_i)`, or `None` if the
+// congruences are incompatible. Panics if any modulus is not positive.
+// See https://en.wikipedia.org/wiki/Chinese_remainder_theorem
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut modulus = 1;
+    for (r, m) in congruences {
+        assert!(*m > 0, "Modulus must be positive");
+        // Want x + modulus * k = r (mod m), i.e. modulus * k = r - x (mod m).
+        let (g, p, _) = extended_gcd(modulus, *m);
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let k = ((diff / g) % step * (p % step)).rem_euclid(step);
+        x += modulus * k;
+        modulus *= step;
+        x = x.rem_euclid(modulus);
+    }
+    Some((x, modulus))
+}
+
+// Each cycle first happens at time `offset` and then repeats every `period`
+// steps after that. Returns the first time all cycles happen simultaneously,
+// or `None` if that never occurs.
+pub fn first_alignment(cycles: &[(u64, u64)]) -> Option<u64> {
+    let congruences: Vec<(i128, i128)> = cycles
+        .iter()
+        .map(|(offset, period)| (*offset as i128, *period as i128))
+        .collect();
+    let (x, modulus) = crt(&congruences)?;
+
+    // The solution must not be before any of the cycles start.
+    let start = cycles.iter().map(|(offset, _)| *offset).max().unwrap_or(0) as i128;
+    let result = if x >= start {
+        x
+    } else {
+        x + (start - x + modulus - 1) / modulus * modulus
+    };
+    u64::try_from(result).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 0), (0, 5), (35, 64)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, crate::num::gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Non-coprime moduli
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 4)]), Some((3, 4)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_first_alignment() {
+        // Cycles starting at zero offset reduce to the lcm.
+        assert_eq!(first_alignment(&[(4, 4), (6, 6)]), Some(12));
+        // The residue solution (2) is before the second cycle starts.
+        assert_eq!(first_alignment(&[(2, 5), (7, 5)]), Some(7));
+        assert_eq!(first_alignment(&[(2, 3), (5, 6)]), Some(5));
+        assert_eq!(first_alignment(&[(0, 2), (1, 2)]), None);
+    }
+}
@@ -0,0 +1,466 @@
+use std::{fmt, str::FromStr};
+
+// Parser combinators for puzzle inputs. Parsers are plain functions from an
+// `Input` to the parsed value and the remaining input, so closures and the
+// functions below can be combined freely. String literals are parsers too,
+// which match themselves:
+//
+//     let node = alphanumeric().skip(" = ").then(delimited("(", pair, ")"));
+//
+// Alternatives backtrack, so no input is consumed by a failed parser.
+
+// Position in the text being parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Input<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self { source, offset: 0 }
+    }
+
+    // The text which has not been parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.source[self.offset..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset == self.source.len()
+    }
+
+    // Skip `n` bytes, which must end on a character boundary.
+    pub fn advance(&self, n: usize) -> Self {
+        Self {
+            source: self.source,
+            offset: self.offset + n,
+        }
+    }
+
+    // 1-based line and column (counted in characters) of the current position.
+    pub fn line_column(&self) -> (usize, usize) {
+        let before = &self.source[..self.offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        let (line, column) = self.line_column();
+        ParseError {
+            line,
+            column,
+            expected: expected.into(),
+            offset: self.offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    // Used to prefer the error which got furthest into the input.
+    offset: usize,
+}
+
+impl ParseError {
+    // Of two alternatives, keep the error from the one which parsed the most.
+    fn furthest(self, other: Self) -> Self {
+        match self.offset.cmp(&other.offset) {
+            std::cmp::Ordering::Less => other,
+            std::cmp::Ordering::Greater => self,
+            std::cmp::Ordering::Equal => Self {
+                expected: format!("{} or {}", self.expected, other.expected),
+                ..self
+            },
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<'a, T> = Result<(T, Input<'a>), ParseError>;
+
+pub trait Parser<'a, T> {
+    fn parse_from(&self, input: Input<'a>) -> ParseResult<'a, T>;
+
+    // Parse the whole of `data`, allowing only trailing whitespace.
+    fn parse(&self, data: &'a str) -> Result<T, ParseError> {
+        let (value, rest) = self.parse_from(Input::new(data))?;
+        let trailing = rest.rest().len() - rest.rest().trim_start().len();
+        let rest = rest.advance(trailing);
+        if rest.is_empty() {
+            Ok(value)
+        } else {
+            Err(rest.error("end of input"))
+        }
+    }
+
+    fn map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        move |input: Input<'a>| {
+            let (value, rest) = self.parse_from(input)?;
+            Ok((f(value), rest))
+        }
+    }
+
+    // Fails with `expected` if `f` returns `None`.
+    fn try_map<U, F>(self, expected: &'static str, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> Option<U>,
+    {
+        move |input: Input<'a>| {
+            let (value, rest) = self.parse_from(input)?;
+            let value = f(value).ok_or_else(|| input.error(expected))?;
+            Ok((value, rest))
+        }
+    }
+
+    // Both parsers in sequence, keeping both values.
+    fn then<U, P>(self, next: P) -> impl Parser<'a, (T, U)>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        move |input: Input<'a>| {
+            let (a, rest) = self.parse_from(input)?;
+            let (b, rest) = next.parse_from(rest)?;
+            Ok(((a, b), rest))
+        }
+    }
+
+    // Both parsers in sequence, keeping only the first value.
+    fn skip<U, P>(self, next: P) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        self.then(next).map(|(a, _)| a)
+    }
+
+    // Try `other` if `self` fails.
+    fn or<P>(self, other: P) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+        P: Parser<'a, T>,
+    {
+        move |input: Input<'a>| {
+            self.parse_from(input)
+                .or_else(|e| other.parse_from(input).map_err(|f| e.furthest(f)))
+        }
+    }
+
+    fn optional(self) -> impl Parser<'a, Option<T>>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| match self.parse_from(input) {
+            Ok((value, rest)) => Ok((Some(value), rest)),
+            Err(_) => Ok((None, input)),
+        }
+    }
+
+    // Zero or more repetitions.
+    fn many(self) -> impl Parser<'a, Vec<T>>
+    where
+        Self: Sized,
+    {
+        move |mut input: Input<'a>| {
+            let mut values = Vec::new();
+            while let Ok((value, rest)) = self.parse_from(input) {
+                if rest == input {
+                    // Would loop forever.
+                    break;
+                }
+                values.push(value);
+                input = rest;
+            }
+            Ok((values, input))
+        }
+    }
+
+    // One or more values separated by `separator`.
+    fn separated_by<U, P>(self, separator: P) -> impl Parser<'a, Vec<T>>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        move |input: Input<'a>| {
+            let (first, mut input) = self.parse_from(input)?;
+            let mut values = vec![first];
+            while let Ok((value, rest)) = separator
+                .parse_from(input)
+                .and_then(|(_, rest)| self.parse_from(rest))
+            {
+                values.push(value);
+                input = rest;
+            }
+            Ok((values, input))
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Input<'a>) -> ParseResult<'a, T>,
+{
+    fn parse_from(&self, input: Input<'a>) -> ParseResult<'a, T> {
+        self(input)
+    }
+}
+
+// A string literal matches exactly itself.
+impl<'a> Parser<'a, &'a str> for &'static str {
+    fn parse_from(&self, input: Input<'a>) -> ParseResult<'a, &'a str> {
+        if input.rest().starts_with(*self) {
+            Ok((&input.rest()[..self.len()], input.advance(self.len())))
+        } else {
+            Err(input.error(format!("{self:?}")))
+        }
+    }
+}
+
+pub fn literal<'a>(s: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| s.parse_from(input)
+}
+
+// The longest non-empty run of characters matching `predicate`.
+pub fn take_while1<'a, F>(expected: &'static str, predicate: F) -> impl Parser<'a, &'a str>
+where
+    F: Fn(char) -> bool,
+{
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let n = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        if n == 0 {
+            return Err(input.error(expected));
+        }
+        Ok((&rest[..n], input.advance(n)))
+    }
+}
+
+// Zero or more spaces or tabs, but not newlines.
+pub fn spaces<'a>() -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let n = rest.find(|c| c != ' ' && c != '\t').unwrap_or(rest.len());
+        Ok((&rest[..n], input.advance(n)))
+    }
+}
+
+// Letters and digits, e.g. node labels and wire names.
+pub fn alphanumeric<'a>() -> impl Parser<'a, &'a str> {
+    take_while1("letters or digits", |c| c.is_ascii_alphanumeric())
+}
+
+pub fn unsigned<'a, T: FromStr>() -> impl Parser<'a, T> {
+    take_while1("digits", |c| c.is_ascii_digit()).try_map("integer in range", |s| s.parse().ok())
+}
+
+// Integer with an optional `+` or `-` sign.
+pub fn signed<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let sign = input.rest().starts_with(['+', '-']) as usize;
+        let (_, rest) =
+            take_while1("digits", |c| c.is_ascii_digit()).parse_from(input.advance(sign))?;
+        let text = &input.rest()[..(rest.offset - input.offset)];
+        let value = text.parse().map_err(|_| input.error("integer in range"))?;
+        Ok((value, rest))
+    }
+}
+
+// The value of the first of `options` whose text matches.
+pub fn one_of<'a, T: Copy>(options: &'static [(&'static str, T)]) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        options
+            .iter()
+            .find(|(text, _)| input.rest().starts_with(text))
+            .map(|(text, value)| (*value, input.advance(text.len())))
+            .ok_or_else(|| {
+                let texts: Vec<String> = options.iter().map(|(t, _)| format!("{t:?}")).collect();
+                input.error(format!("one of {}", texts.join(", ")))
+            })
+    }
+}
+
+// `inner` surrounded by `open` and `close`.
+pub fn delimited<'a, A, T, B>(
+    open: impl Parser<'a, A>,
+    inner: impl Parser<'a, T>,
+    close: impl Parser<'a, B>,
+) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let (_, rest) = open.parse_from(input)?;
+        let (value, rest) = inner.parse_from(rest)?;
+        let (_, rest) = close.parse_from(rest)?;
+        Ok((value, rest))
+    }
+}
+
+// `value` after `prefix`.
+pub fn preceded<'a, A, T>(
+    prefix: impl Parser<'a, A>,
+    value: impl Parser<'a, T>,
+) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let (_, rest) = prefix.parse_from(input)?;
+        value.parse_from(rest)
+    }
+}
+
+// A field such as `Register A: 729` or `x=787`, where `label` is followed
+// by optional spaces and then the value.
+pub fn labelled<'a, T>(label: &'static str, value: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    preceded(preceded(label, spaces()), value)
+}
+
+// One or more values separated by `separator`.
+pub fn separated<'a, T, U>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, U>,
+) -> impl Parser<'a, Vec<T>> {
+    item.separated_by(separator)
+}
+
+// One value per line, up to the end of the input or a blank line. Unlike
+// `separated`, every such line has to parse, so errors point at the bad line.
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    committed_list(item, "\n", |rest| rest.is_empty() || rest.starts_with('\n'))
+}
+
+// Blocks of lines separated by blank lines, up to the end of the input.
+pub fn blocks<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    committed_list(item, "\n\n", |rest| rest.trim_start().is_empty())
+}
+
+// One or more values separated by `separator`, where a value must follow
+// each separator unless `at_end` holds for the remaining text.
+fn committed_list<'a, T>(
+    item: impl Parser<'a, T>,
+    separator: &'static str,
+    at_end: impl Fn(&str) -> bool,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut input) = item.parse_from(input)?;
+        let mut values = vec![first];
+        while let Ok((_, rest)) = separator.parse_from(input) {
+            if at_end(rest.rest()) {
+                break;
+            }
+            let (value, rest) = item.parse_from(rest)?;
+            values.push(value);
+            input = rest;
+        }
+        Ok((values, input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Operator {
+        Lt,
+        Gt,
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(unsigned::<u8>().parse("255"), Ok(255));
+        assert_eq!(signed::<i64>().parse("-42"), Ok(-42));
+        assert_eq!(signed::<i64>().parse("+7"), Ok(7));
+        let error = unsigned::<u8>().parse("256").unwrap_err();
+        assert_eq!(error.expected, "integer in range");
+        assert!(signed::<i32>().parse("-").is_err());
+        assert!(unsigned::<u32>().parse("-1").is_err());
+        assert_eq!(
+            separated(signed::<i32>(), ",").parse("3,-5,4\n"),
+            Ok(vec![3, -5, 4])
+        );
+    }
+
+    #[test]
+    fn test_workflow() {
+        let condition = one_of(&[("x", 0), ("m", 1), ("a", 2), ("s", 3)])
+            .then(one_of(&[("<", Operator::Lt), (">", Operator::Gt)]))
+            .then(unsigned::<u64>())
+            .skip(":")
+            .then(alphanumeric())
+            .map(|(((axis, op), value), target)| (axis, op, value, target));
+        let workflow = alphanumeric().then(delimited(
+            "{",
+            condition.skip(",").many().then(alphanumeric()),
+            "}",
+        ));
+        let (name, (conditions, fallback)) = workflow.parse("px{a<2006:qkq,m>2090:A,rhg}").unwrap();
+        assert_eq!(name, "px");
+        assert_eq!(
+            conditions,
+            vec![(2, Operator::Lt, 2006, "qkq"), (1, Operator::Gt, 2090, "A")]
+        );
+        assert_eq!(fallback, "rhg");
+
+        let part = delimited(
+            "{",
+            separated(alphanumeric().skip("=").then(unsigned::<u64>()), ","),
+            "}",
+        );
+        assert_eq!(
+            part.parse("{x=787,m=2655}"),
+            Ok(vec![("x", 787), ("m", 2655)])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let register = || labelled("Register A:", unsigned::<u64>());
+        let program = lines(register())
+            .skip("\n\n")
+            .then(blocks(lines(alphanumeric())));
+        let (registers, blocks) = program
+            .parse("Register A: 729\nRegister A:3\n\nab\ncd\n\nef\n")
+            .unwrap();
+        assert_eq!(registers, vec![729, 3]);
+        assert_eq!(blocks, vec![vec!["ab", "cd"], vec!["ef"]]);
+
+        let error = program
+            .parse("Register A: 729\nRegister A: x\n\nab")
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 13));
+        assert_eq!(error.expected, "digits");
+        let error = separated(register(), "\n")
+            .parse("Register A: 1\nRegister A: x")
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "end of input");
+
+        let error = delimited("(", alphanumeric(), ")")
+            .parse("(ab]")
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (1, 4));
+        assert_eq!(error.to_string(), "line 1, column 4: expected \")\"");
+
+        let op = literal("AND").or("OR").or("XOR");
+        assert_eq!(op.parse("XOR"), Ok("XOR"));
+        let error = op.parse("NOT").unwrap_err();
+        assert_eq!(error.expected, "\"AND\" or \"OR\" or \"XOR\"");
+    }
+}
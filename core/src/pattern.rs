@@ -0,0 +1,595 @@
+use {
+    crate::bitset::{BitSet, GrowableBitSet},
+    std::{collections::HashMap, fmt, ops::Range, str::FromStr},
+};
+
+// Small regular expression engine over bytes, supporting
+//
+//  - literals, with `\` escaping any special character,
+//  - `.`, `\d`, `\w`, `\s` and classes such as `[a-z_]` or `[^,]`,
+//  - repeats `*`, `+`, `?`, `{n}`, `{n,}` and `{m,n}` (all greedy),
+//  - alternation `|` and capture groups `(...)`.
+//
+// Patterns are compiled to a DFA which finds the leftmost-longest matches.
+// Captures are then filled in by walking the NFA over just the matched text.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    // Byte offset in the pattern.
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+// Set of bytes.
+type Class = BitSet<4>;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Class(Class),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+    Group(usize, Box<Node>),
+}
+
+struct PatternParser<'p> {
+    pattern: &'p [u8],
+    position: usize,
+    n_groups: usize,
+}
+
+impl PatternParser<'_> {
+    fn error(&self, message: &'static str) -> PatternError {
+        PatternError {
+            position: self.position,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.pattern.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn expect(&mut self, c: u8, message: &'static str) -> Result<(), PatternError> {
+        if self.next() == Some(c) {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn alternation(&mut self) -> Result<Node, PatternError> {
+        let mut alternatives = vec![self.concat()?];
+        while self.peek() == Some(b'|') {
+            self.position += 1;
+            alternatives.push(self.concat()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().expect("Has one alternative")
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == b'|' || c == b')' {
+                break;
+            }
+            nodes.push(self.repeat()?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn repeat(&mut self) -> Result<Node, PatternError> {
+        let mut node = self.atom()?;
+        loop {
+            let (min, max) = match self.peek() {
+                Some(b'*' | b'+' | b'?' | b'{') => match self.next() {
+                    Some(b'*') => (0, None),
+                    Some(b'+') => (1, None),
+                    Some(b'?') => (0, Some(1)),
+                    _ => self.bounds()?,
+                },
+                _ => return Ok(node),
+            };
+            node = Node::Repeat {
+                node: Box::new(node),
+                min,
+                max,
+            };
+        }
+    }
+
+    // The inside of `{n}`, `{n,}` or `{m,n}`, including the closing brace.
+    fn bounds(&mut self) -> Result<(usize, Option<usize>), PatternError> {
+        let min = self.number()?;
+        let max = if self.peek() == Some(b',') {
+            self.position += 1;
+            if self.peek() == Some(b'}') {
+                None
+            } else {
+                Some(self.number()?)
+            }
+        } else {
+            Some(min)
+        };
+        self.expect(b'}', "Expected `}`")?;
+        if max.is_some_and(|max| max < min) {
+            return Err(self.error("Repeat bounds out of order"));
+        }
+        Ok((min, max))
+    }
+
+    fn number(&mut self) -> Result<usize, PatternError> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.pattern[start..self.position])
+            .expect("Digits are ASCII")
+            .parse()
+            .map_err(|_| self.error("Expected a repeat count"))
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        let c = self.next().ok_or_else(|| self.error("Unexpected end"))?;
+        let class = match c {
+            b'(' => {
+                self.n_groups += 1;
+                let index = self.n_groups;
+                let inner = self.alternation()?;
+                self.expect(b')', "Unclosed group")?;
+                return Ok(Node::Group(index, Box::new(inner)));
+            }
+            b'[' => self.class()?,
+            b'.' => (0..256).collect(),
+            b'\\' => self.escape()?,
+            b'*' | b'+' | b'?' | b'{' => return Err(self.error("Nothing to repeat")),
+            _ => Class::from_iter([c as usize]),
+        };
+        Ok(Node::Class(class))
+    }
+
+    fn escape(&mut self) -> Result<Class, PatternError> {
+        let c = self.next().ok_or_else(|| self.error("Unfinished escape"))?;
+        let bytes = |f: fn(&u8) -> bool| (0..=255_u8).filter(f).map(usize::from).collect();
+        Ok(match c {
+            b'd' => bytes(u8::is_ascii_digit),
+            b'w' => bytes(|c| c.is_ascii_alphanumeric() || *c == b'_'),
+            b's' => bytes(u8::is_ascii_whitespace),
+            b'n' => Class::from_iter([b'\n' as usize]),
+            _ => Class::from_iter([c as usize]),
+        })
+    }
+
+    // The inside of `[...]`, including the closing bracket.
+    fn class(&mut self) -> Result<Class, PatternError> {
+        let negated = self.peek() == Some(b'^');
+        if negated {
+            self.position += 1;
+        }
+        let mut class = Class::new();
+        loop {
+            let c = self.next().ok_or_else(|| self.error("Unclosed class"))?;
+            match c {
+                b']' => break,
+                b'\\' => class |= self.escape()?,
+                _ if self.peek() == Some(b'-')
+                    && self
+                        .pattern
+                        .get(self.position + 1)
+                        .is_some_and(|e| *e != b']') =>
+                {
+                    let end = self.pattern[self.position + 1];
+                    self.position += 2;
+                    if end < c {
+                        return Err(self.error("Class range out of order"));
+                    }
+                    class |= (c..=end).map(usize::from).collect();
+                }
+                _ => {
+                    class.insert(c as usize);
+                }
+            }
+        }
+        if negated {
+            class ^= (0..256).collect();
+        }
+        Ok(class)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Inst {
+    // Consume a byte from the class and continue with the next instruction.
+    Class(Class),
+    // Continue at both, preferring the first.
+    Split(usize, usize),
+    Jump(usize),
+    // Record the current position in a capture slot.
+    Save(usize),
+    Match,
+}
+
+fn compile(node: &Node, program: &mut Vec<Inst>) {
+    match node {
+        Node::Class(class) => program.push(Inst::Class(*class)),
+        Node::Concat(nodes) => {
+            for node in nodes {
+                compile(node, program);
+            }
+        }
+        Node::Alternation(alternatives) => {
+            let mut jumps = Vec::new();
+            for (i, alternative) in alternatives.iter().enumerate() {
+                let split = program.len();
+                if i + 1 < alternatives.len() {
+                    program.push(Inst::Split(split + 1, usize::MAX));
+                }
+                compile(alternative, program);
+                if i + 1 < alternatives.len() {
+                    jumps.push(program.len());
+                    program.push(Inst::Jump(usize::MAX));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Inst::Jump(end);
+            }
+        }
+        Node::Repeat { node, min, max } => {
+            for _ in 0..*min {
+                compile(node, program);
+            }
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, usize::MAX));
+                    compile(node, program);
+                    program.push(Inst::Jump(split));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Inst::Split(program.len() + 1, usize::MAX));
+                        compile(node, program);
+                    }
+                    let end = program.len();
+                    for split in splits {
+                        program[split] = Inst::Split(split + 1, end);
+                    }
+                }
+            }
+        }
+        Node::Group(index, node) => {
+            program.push(Inst::Save(2 * index));
+            compile(node, program);
+            program.push(Inst::Save(2 * index + 1));
+        }
+    }
+}
+
+const DEAD: u32 = u32::MAX;
+
+// Deterministic automaton for the whole pattern, anchored at the start.
+#[derive(Debug, Clone)]
+struct Dfa {
+    transitions: Vec<[u32; 256]>,
+    accepting: Vec<bool>,
+}
+
+impl Dfa {
+    // Subset construction, where each DFA state is the set of `Class` and
+    // `Match` instructions reachable without consuming input.
+    fn new(program: &[Inst]) -> Self {
+        let mut ids: HashMap<Vec<usize>, u32> = HashMap::new();
+        let mut sets = Vec::new();
+        let mut dfa = Self {
+            transitions: Vec::new(),
+            accepting: Vec::new(),
+        };
+        let mut add = |set: Vec<usize>, dfa: &mut Self, sets: &mut Vec<Vec<usize>>| -> u32 {
+            if set.is_empty() {
+                return DEAD;
+            }
+            *ids.entry(set.clone()).or_insert_with(|| {
+                dfa.accepting
+                    .push(set.iter().any(|pc| matches!(program[*pc], Inst::Match)));
+                dfa.transitions.push([DEAD; 256]);
+                sets.push(set);
+                (sets.len() - 1) as u32
+            })
+        };
+
+        add(closure(program, [0]), &mut dfa, &mut sets);
+        let mut next = 0;
+        while next < sets.len() {
+            for byte in 0..256 {
+                let targets = sets[next].iter().filter_map(|pc| match &program[*pc] {
+                    Inst::Class(class) if class.contains(byte) => Some(pc + 1),
+                    _ => None,
+                });
+                let set = closure(program, targets);
+                let target = add(set, &mut dfa, &mut sets);
+                dfa.transitions[next][byte] = target;
+            }
+            next += 1;
+        }
+        dfa
+    }
+
+    // End of the longest match starting at `start`, if any.
+    fn longest_match(&self, haystack: &[u8], start: usize) -> Option<usize> {
+        let mut state = 0;
+        let mut end = self.accepting[0].then_some(start);
+        for (i, byte) in haystack[start..].iter().enumerate() {
+            state = self.transitions[state as usize][*byte as usize];
+            if state == DEAD {
+                break;
+            }
+            if self.accepting[state as usize] {
+                end = Some(start + i + 1);
+            }
+        }
+        end
+    }
+}
+
+// Sorted `Class` and `Match` instructions reachable from `pcs` without
+// consuming input.
+fn closure(program: &[Inst], pcs: impl IntoIterator<Item = usize>) -> Vec<usize> {
+    let mut seen = GrowableBitSet::with_capacity(program.len());
+    let mut stack: Vec<usize> = pcs.into_iter().collect();
+    let mut result = Vec::new();
+    while let Some(pc) = stack.pop() {
+        if !seen.insert(pc) {
+            continue;
+        }
+        match program[pc] {
+            Inst::Class(_) | Inst::Match => result.push(pc),
+            Inst::Split(a, b) => stack.extend([b, a]),
+            Inst::Jump(target) => stack.push(target),
+            Inst::Save(_) => stack.push(pc + 1),
+        }
+    }
+    result.sort_unstable();
+    result
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    program: Vec<Inst>,
+    dfa: Dfa,
+    n_groups: usize,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut parser = PatternParser {
+            pattern: pattern.as_bytes(),
+            position: 0,
+            n_groups: 0,
+        };
+        let root = parser.alternation()?;
+        if parser.position < pattern.len() {
+            return Err(parser.error("Unmatched `)`"));
+        }
+        let mut program = Vec::new();
+        compile(&Node::Group(0, Box::new(root)), &mut program);
+        program.push(Inst::Match);
+        Ok(Self {
+            dfa: Dfa::new(&program),
+            program,
+            n_groups: parser.n_groups,
+        })
+    }
+
+    // Whether the whole of `haystack` matches.
+    pub fn is_match(&self, haystack: &[u8]) -> bool {
+        self.dfa.longest_match(haystack, 0) == Some(haystack.len())
+    }
+
+    // Ranges of the leftmost-longest, non-overlapping matches in `haystack`.
+    pub fn find_iter<'h>(
+        &self,
+        haystack: &'h [u8],
+    ) -> impl Iterator<Item = Range<usize>> + use<'_, 'h> {
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            while start <= haystack.len() {
+                let Some(end) = self.dfa.longest_match(haystack, start) else {
+                    start += 1;
+                    continue;
+                };
+                let found = start..end;
+                // Step past empty matches so the search makes progress.
+                start = if end == start { end + 1 } else { end };
+                return Some(found);
+            }
+            None
+        })
+    }
+
+    // Like `find_iter`, with the capture groups of each match.
+    pub fn captures_iter<'h>(
+        &self,
+        haystack: &'h [u8],
+    ) -> impl Iterator<Item = Captures<'h>> + use<'_, 'h> {
+        self.find_iter(haystack).map(move |found| Captures {
+            haystack,
+            slots: self.capture_slots(haystack, found),
+        })
+    }
+
+    // Find the highest priority path through the program which matches
+    // exactly `found`, and record where it passes each `Save`.
+    fn capture_slots(&self, haystack: &[u8], found: Range<usize>) -> Vec<Option<usize>> {
+        enum Job {
+            Explore(usize, usize),
+            Restore(usize, Option<usize>),
+        }
+        let width = found.len() + 1;
+        // A failed (instruction, position) pair fails whatever the captures are.
+        let mut visited = GrowableBitSet::with_capacity(self.program.len() * width);
+        let mut slots = vec![None; 2 * (self.n_groups + 1)];
+        let mut stack = vec![Job::Explore(0, found.start)];
+        while let Some(job) = stack.pop() {
+            let (mut pc, mut pos) = match job {
+                Job::Restore(slot, old) => {
+                    slots[slot] = old;
+                    continue;
+                }
+                Job::Explore(pc, pos) => (pc, pos),
+            };
+            while visited.insert(pc * width + (pos - found.start)) {
+                match &self.program[pc] {
+                    Inst::Class(class) => {
+                        if pos < found.end && class.contains(haystack[pos] as usize) {
+                            pc += 1;
+                            pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    Inst::Split(a, b) => {
+                        stack.push(Job::Explore(*b, pos));
+                        pc = *a;
+                    }
+                    Inst::Jump(target) => pc = *target,
+                    Inst::Save(slot) => {
+                        stack.push(Job::Restore(*slot, slots[*slot]));
+                        slots[*slot] = Some(pos);
+                        pc += 1;
+                    }
+                    Inst::Match => {
+                        if pos == found.end {
+                            return slots;
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        unreachable!("The DFA found a match")
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+// Capture groups of a single match. Group 0 is the whole match and the
+// other groups are numbered by their opening parenthesis, from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures<'h> {
+    haystack: &'h [u8],
+    slots: Vec<Option<usize>>,
+}
+
+impl<'h> Captures<'h> {
+    // `None` if the group did not take part in the match.
+    pub fn range(&self, group: usize) -> Option<Range<usize>> {
+        Some(self.slots[2 * group]?..self.slots[2 * group + 1]?)
+    }
+
+    pub fn get(&self, group: usize) -> Option<&'h [u8]> {
+        Some(&self.haystack[self.range(group)?])
+    }
+
+    // The text of the group, parsed. `None` if it did not take part or does not parse.
+    pub fn parse<T: FromStr>(&self, group: usize) -> Option<T> {
+        std::str::from_utf8(self.get(group)?).ok()?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(pattern: &str, haystack: &str) -> Vec<String> {
+        let pattern = Pattern::new(pattern).unwrap();
+        pattern
+            .find_iter(haystack.as_bytes())
+            .map(|r| haystack[r].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(texts(r"\d+", "a12b345c6"), vec!["12", "345", "6"]);
+        assert_eq!(texts("ab|abc", "xabcab"), vec!["abc", "ab"]);
+        assert_eq!(texts("a{2,3}", "aaaaaaa"), vec!["aaa", "aaa"]);
+        assert_eq!(texts("[a-c]+x?", "abxcd-ca"), vec!["abx", "c", "ca"]);
+        assert_eq!(texts("[^,]+", "ab,c,,d"), vec!["ab", "c", "d"]);
+        assert_eq!(texts("x*", "ab"), vec!["", "", ""]);
+        assert!(Pattern::new("(a|b)*c").unwrap().is_match(b"abbac"));
+        assert!(!Pattern::new("(a|b)*c").unwrap().is_match(b"abbacd"));
+    }
+
+    #[test]
+    fn test_captures() {
+        let pattern = Pattern::new(r"mul\((\d{1,3}),(\d{1,3})\)|do(n't)?\(\)").unwrap();
+        let haystack = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let found: Vec<(Option<u64>, Option<u64>, bool)> = pattern
+            .captures_iter(haystack)
+            .map(|c| (c.parse(1), c.parse(2), c.get(3).is_some()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(2), Some(4), false),
+                (None, None, true),
+                (Some(5), Some(5), false),
+                (Some(11), Some(8), false),
+                (None, None, false),
+                (Some(8), Some(5), false),
+            ]
+        );
+
+        // Greedy groups take as much as they can.
+        let pattern = Pattern::new("(a*)(a*)").unwrap();
+        let captures = pattern.captures_iter(b"aaa").next().unwrap();
+        assert_eq!(captures.range(0), Some(0..3));
+        assert_eq!(captures.range(1), Some(0..3));
+        assert_eq!(captures.range(2), Some(3..3));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Pattern::new("(ab").unwrap_err().message, "Unclosed group");
+        assert_eq!(Pattern::new("ab)").unwrap_err().position, 2);
+        assert_eq!(
+            Pattern::new("a{3,1}").unwrap_err().message,
+            "Repeat bounds out of order"
+        );
+        assert_eq!(Pattern::new("*a").unwrap_err().message, "Nothing to repeat");
+        assert!(Pattern::new("[z-a]").is_err());
+    }
+}
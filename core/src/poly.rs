@@ -0,0 +1,272 @@
+use {
+    crate::rational::{Rational, SignedInteger},
+    std::fmt,
+};
+
+// Polynomial with exact rational coefficients, stored lowest degree first.
+// Trailing zero coefficients are removed, so the zero polynomial has no coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial<T> {
+    coefficients: Vec<Rational<T>>,
+}
+
+impl<T: SignedInteger> Polynomial<T> {
+    pub fn new(mut coefficients: Vec<Rational<T>>) -> Self {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn from_integers<I: IntoIterator<Item = T>>(coefficients: I) -> Self {
+        Self::new(coefficients.into_iter().map(Rational::integer).collect())
+    }
+
+    // Unique polynomial of smallest degree passing through all the given `(x, y)` points.
+    // Built from Newton's divided differences, which gives the same polynomial as the
+    // Lagrange form. Returns `None` if two points share the same `x`.
+    // See https://en.wikipedia.org/wiki/Newton_polynomial
+    pub fn interpolate(points: &[(T, T)]) -> Option<Self> {
+        let xs: Vec<Rational<T>> = points.iter().map(|(x, _)| Rational::integer(*x)).collect();
+        let mut table: Vec<Rational<T>> =
+            points.iter().map(|(_, y)| Rational::integer(*y)).collect();
+
+        // After step `k`, `table[i]` holds the divided difference f[x_{i-k}, ..., x_i].
+        for k in 1..points.len() {
+            for i in (k..points.len()).rev() {
+                let dx = xs[i] - xs[i - k];
+                if dx.is_zero() {
+                    return None;
+                }
+                table[i] = (table[i] - table[i - 1]) / dx;
+            }
+        }
+
+        // Expand the nested Newton form c_0 + (x - x_0)(c_1 + (x - x_1)(c_2 + ...)).
+        let mut result = Self::new(Vec::new());
+        for (c, x) in table.into_iter().zip(xs).rev() {
+            result = result.mul_linear(x);
+            result = result.add_constant(c);
+        }
+        Some(result)
+    }
+
+    pub fn coefficients(&self) -> &[Rational<T>] {
+        &self.coefficients
+    }
+
+    // `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn evaluate(&self, x: Rational<T>) -> Rational<T> {
+        // Horner's method
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Rational::ZERO, |acc, c| acc * x + *c)
+    }
+
+    // Returns `None` if the value at `x` is not an integer.
+    pub fn evaluate_integer(&self, x: T) -> Option<T> {
+        self.evaluate(Rational::integer(x)).to_integer()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let n = self.coefficients.len().max(other.coefficients.len());
+        let coefficients = (0..n)
+            .map(|i| {
+                let a = self.coefficients.get(i).copied().unwrap_or(Rational::ZERO);
+                let b = other.coefficients.get(i).copied().unwrap_or(Rational::ZERO);
+                a + b
+            })
+            .collect();
+        Self::new(coefficients)
+    }
+
+    // Multiply by `(x - root)`.
+    fn mul_linear(&self, root: Rational<T>) -> Self {
+        let mut coefficients = vec![Rational::ZERO; self.coefficients.len() + 1];
+        for (i, c) in self.coefficients.iter().enumerate() {
+            coefficients[i + 1] += *c;
+            coefficients[i] -= *c * root;
+        }
+        Self::new(coefficients)
+    }
+
+    fn add_constant(mut self, c: Rational<T>) -> Self {
+        match self.coefficients.first_mut() {
+            Some(first) => *first += c,
+            None => self.coefficients.push(c),
+        }
+        Self::new(self.coefficients)
+    }
+}
+
+impl<T: SignedInteger> fmt::Display for Polynomial<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+        let mut first = true;
+        for (i, c) in self.coefficients.iter().enumerate().rev() {
+            if c.is_zero() {
+                continue;
+            }
+            if !first {
+                write!(f, " + ")?;
+            }
+            first = false;
+            match i {
+                0 => write!(f, "{c}")?,
+                1 => write!(f, "({c})x")?,
+                _ => write!(f, "({c})x^{i}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Newton forward differences of a sequence sampled at `0, 1, 2, ...`.
+// Exact over the integers: if the samples come from a polynomial with integer
+// values then every extrapolated value is an integer too.
+// See https://en.wikipedia.org/wiki/Finite_difference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Differences<T> {
+    // `leading[k]` is the k-th forward difference at 0.
+    leading: Vec<T>,
+}
+
+impl<T: SignedInteger> Differences<T> {
+    pub fn from_samples(samples: &[T]) -> Self {
+        let mut row = samples.to_vec();
+        let mut leading = Vec::with_capacity(samples.len());
+        while let Some(first) = row.first() {
+            leading.push(*first);
+            row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+        Self { leading }
+    }
+
+    // Degree of the lowest degree polynomial through the samples, provided there
+    // are enough samples to confirm it (i.e. the differences reached a row of zeros).
+    // Returns `None` if every sample was needed, since then any higher degree is
+    // equally consistent with the data. The zero sequence has degree 0.
+    pub fn degree(&self) -> Option<usize> {
+        let last_nonzero = self.leading.iter().rposition(|d| *d != T::ZERO);
+        match last_nonzero {
+            None => Some(0),
+            Some(d) if d + 1 < self.leading.len() => Some(d),
+            Some(_) => None,
+        }
+    }
+
+    // Value of the interpolating polynomial at position `n`. Positions past the
+    // samples extrapolate forward, negative positions extrapolate backward.
+    pub fn extrapolate(&self, n: T) -> T {
+        // f(n) = sum_k binomial(n, k) * delta^k f(0), which holds for negative `n`
+        // too using the generalized binomial coefficient.
+        // Trailing zero differences are skipped, as their binomial coefficients
+        // can overflow even when the result is small.
+        let n_terms = self
+            .leading
+            .iter()
+            .rposition(|d| *d != T::ZERO)
+            .map_or(0, |i| i + 1);
+        let mut total = T::ZERO;
+        let mut binomial = T::ONE;
+        let mut k = T::ZERO;
+        for (i, d) in self.leading[..n_terms].iter().enumerate() {
+            if i > 0 {
+                // binomial(n, k + 1) = binomial(n, k) * (n - k) / (k + 1), and the division is exact.
+                binomial = binomial * (n - k) / (k + T::ONE);
+                k = k + T::ONE;
+            }
+            total = total + binomial * *d;
+        }
+        total
+    }
+
+    pub fn to_polynomial(&self) -> Polynomial<T> {
+        let mut result = Polynomial::new(Vec::new());
+        let mut binomial = Polynomial::from_integers([T::ONE]);
+        let mut k = Rational::ZERO;
+        for d in &self.leading {
+            let term = binomial
+                .coefficients
+                .iter()
+                .map(|c| *c * Rational::integer(*d))
+                .collect();
+            result = result.add(&Polynomial::new(term));
+            k += Rational::ONE;
+            let scale = k.recip();
+            binomial = binomial.mul_linear(k - Rational::ONE);
+            binomial.coefficients.iter_mut().for_each(|c| *c *= scale);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        // y = x^2 / 2 + x / 2, the triangular numbers
+        let points = [(0, 0), (3, 6), (1, 1)];
+        let poly = Polynomial::interpolate(&points).unwrap();
+        assert_eq!(poly.degree(), Some(2));
+        assert_eq!(
+            poly.coefficients(),
+            &[Rational::ZERO, Rational::new(1, 2), Rational::new(1, 2)]
+        );
+        assert_eq!(poly.evaluate_integer(10_i64), Some(55));
+        assert_eq!(poly.to_string(), "(1/2)x^2 + (1/2)x");
+
+        // Collinear points give a line
+        let poly = Polynomial::interpolate(&[(1, 3), (2, 5), (4, 9)]).unwrap();
+        assert_eq!(poly, Polynomial::from_integers([1, 2]));
+
+        assert_eq!(Polynomial::<i64>::interpolate(&[(1, 3), (1, 4)]), None);
+        assert_eq!(Polynomial::<i64>::interpolate(&[]).unwrap().degree(), None);
+    }
+
+    #[test]
+    fn test_differences() {
+        let samples = [1, 3, 6, 10, 15, 21];
+        let differences = Differences::from_samples(&samples);
+        assert_eq!(differences.degree(), Some(2));
+        assert_eq!(differences.extrapolate(6), 28);
+        assert_eq!(differences.extrapolate(-1), 0);
+        assert_eq!(differences.extrapolate(-2), 0);
+        assert_eq!(differences.extrapolate(-3), 1);
+
+        // Many samples of a quadratic, where `binomial(10^6, 20)` would overflow.
+        let samples: Vec<i64> = (0..21).map(|n| (n + 1) * (n + 2) / 2).collect();
+        let differences = Differences::from_samples(&samples);
+        assert_eq!(differences.extrapolate(1_000_000), 500_001_500_001);
+
+        // Not enough samples to confirm the degree
+        assert_eq!(Differences::from_samples(&[1, 2, 4]).degree(), None);
+        assert_eq!(Differences::from_samples(&[0, 0]).degree(), Some(0));
+    }
+
+    #[test]
+    fn test_differences_match_interpolation() {
+        let samples = [10_i64, 13, 16, 21, 30, 45];
+        let differences = Differences::from_samples(&samples);
+        let points: Vec<(i64, i64)> = samples
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(i, y)| (i as i64, y))
+            .collect();
+        let poly = Polynomial::interpolate(&points).unwrap();
+        assert_eq!(differences.to_polynomial(), poly);
+        for n in -5..20 {
+            assert_eq!(poly.evaluate_integer(n), Some(differences.extrapolate(n)));
+        }
+    }
+}
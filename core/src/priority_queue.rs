@@ -0,0 +1,328 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+// Which end of the priorities an `IndexedHeap` pops first.
+pub trait HeapOrder {
+    fn before<P: Ord>(a: &P, b: &P) -> bool;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinFirst;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxFirst;
+
+impl HeapOrder for MinFirst {
+    fn before<P: Ord>(a: &P, b: &P) -> bool {
+        a < b
+    }
+}
+
+impl HeapOrder for MaxFirst {
+    fn before<P: Ord>(a: &P, b: &P) -> bool {
+        a > b
+    }
+}
+
+// Binary heap holding each key at most once, which tracks where every key
+// is so its priority can be changed in place. Searches can then move a node
+// forwards instead of pushing a duplicate and skipping stale entries later.
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K, P, O = MinFirst> {
+    // Priorities in heap order, each with the slot of its key.
+    heap: Vec<(P, usize)>,
+    // Each key with its position in `heap`. Keys are only hashed when looked
+    // up, moving entries around the heap just updates the slots.
+    slots: Vec<(K, usize)>,
+    // Slots whose keys have been popped, to be reused.
+    free: Vec<usize>,
+    index: HashMap<K, usize>,
+    order: PhantomData<O>,
+}
+
+pub type IndexedMinHeap<K, P> = IndexedHeap<K, P, MinFirst>;
+pub type IndexedMaxHeap<K, P> = IndexedHeap<K, P, MaxFirst>;
+
+impl<K, P, O> Default for IndexedHeap<K, P, O> {
+    fn default() -> Self {
+        Self {
+            heap: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            index: HashMap::new(),
+            order: PhantomData,
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, P: Ord, O: HeapOrder> IndexedHeap<K, P, O> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        let slot = *self.index.get(key)?;
+        Some(&self.heap[self.slots[slot].1].0)
+    }
+
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap
+            .first()
+            .map(|(priority, slot)| (&self.slots[*slot].0, priority))
+    }
+
+    // Inserts the key, or sets its priority if already present.
+    // Returns the previous priority.
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if let Some(&slot) = self.index.get(&key) {
+            let i = self.slots[slot].1;
+            let old = std::mem::replace(&mut self.heap[i].0, priority);
+            self.restore(i);
+            return Some(old);
+        }
+        let i = self.heap.len();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot] = (key.clone(), i);
+                slot
+            }
+            None => {
+                self.slots.push((key.clone(), i));
+                self.slots.len() - 1
+            }
+        };
+        self.index.insert(key, slot);
+        self.heap.push((priority, slot));
+        self.sift_up(i);
+        None
+    }
+
+    // Moves the key forward to `priority` if that comes before its current
+    // priority (i.e. is lower in a min-heap), inserting it if absent.
+    // Returns whether the heap changed.
+    pub fn decrease_key(&mut self, key: K, priority: P) -> bool {
+        let Some(&slot) = self.index.get(&key) else {
+            self.push(key, priority);
+            return true;
+        };
+        let i = self.slots[slot].1;
+        if !O::before(&priority, &self.heap[i].0) {
+            return false;
+        }
+        self.heap[i].0 = priority;
+        self.sift_up(i);
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        self.remove_at(0)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let slot = *self.index.get(key)?;
+        self.remove_at(self.slots[slot].1).map(|(_, p)| p)
+    }
+
+    fn remove_at(&mut self, i: usize) -> Option<(K, P)> {
+        if i >= self.heap.len() {
+            return None;
+        }
+        let (priority, slot) = self.heap.swap_remove(i);
+        if i < self.heap.len() {
+            self.slots[self.heap[i].1].1 = i;
+            self.restore(i);
+        }
+        let (key, _) = self
+            .index
+            .remove_entry(&self.slots[slot].0)
+            .expect("Slots are indexed");
+        self.free.push(slot);
+        Some((key, priority))
+    }
+
+    // Move the entry at `i` to where it belongs after its priority changed.
+    fn restore(&mut self, i: usize) {
+        let i = self.sift_up(i);
+        self.sift_down(i);
+    }
+
+    fn sift_up(&mut self, mut i: usize) -> usize {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !O::before(&self.heap[i].0, &self.heap[parent].0) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+        i
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut first = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && O::before(&self.heap[child].0, &self.heap[first].0) {
+                    first = child;
+                }
+            }
+            if first == i {
+                return;
+            }
+            self.swap(i, first);
+            i = first;
+        }
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.slots[self.heap[i].1].1 = i;
+        self.slots[self.heap[j].1].1 = j;
+    }
+}
+
+impl<K: Clone + Eq + Hash, P: Ord, O: HeapOrder> FromIterator<(K, P)> for IndexedHeap<K, P, O> {
+    fn from_iter<I: IntoIterator<Item = (K, P)>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for (key, priority) in iter {
+            result.push(key, priority);
+        }
+        result
+    }
+}
+
+// Monotone priority queue for small integer priorities, with one bucket per
+// priority. Popped priorities never decrease, so items can only be pushed at
+// or after the last popped priority, as in Dijkstra's algorithm with
+// integer weights.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+    len: usize,
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self {
+            buckets: Vec::new(),
+            current: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Panics if `priority` is below the last popped priority.
+    pub fn push(&mut self, priority: usize, item: T) {
+        assert!(
+            priority >= self.current,
+            "Priority {priority} is below the last popped {}",
+            self.current
+        );
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.len += 1;
+    }
+
+    // Items with the same priority come out in the reverse order they were pushed.
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+        while self.buckets[self.current].is_empty() {
+            self.current += 1;
+        }
+        self.len -= 1;
+        let item = self.buckets[self.current]
+            .pop()
+            .expect("Bucket is not empty");
+        Some((self.current, item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indexed_heap() {
+        let mut heap: IndexedMinHeap<char, u32> = [('a', 5), ('b', 3), ('c', 8), ('d', 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.peek(), Some((&'d', &1)));
+        assert!(heap.decrease_key('c', 2));
+        assert!(!heap.decrease_key('a', 6));
+        assert!(heap.decrease_key('e', 4));
+        assert_eq!(heap.priority(&'a'), Some(&5));
+        assert_eq!(heap.push('d', 7), Some(1));
+        assert_eq!(heap.remove(&'b'), Some(3));
+        assert!(!heap.contains(&'b'));
+
+        let mut order = Vec::new();
+        while let Some(entry) = heap.pop() {
+            order.push(entry);
+        }
+        assert_eq!(order, vec![('c', 2), ('e', 4), ('a', 5), ('d', 7)]);
+        assert!(heap.is_empty());
+
+        let mut heap: IndexedMaxHeap<u32, i64> =
+            (0..20).map(|i| (i, (i * 7 % 20) as i64)).collect();
+        assert!(heap.decrease_key(3, 100));
+        assert!(!heap.decrease_key(4, -1));
+        let priorities: Vec<i64> = std::iter::from_fn(|| heap.pop()).map(|(_, p)| p).collect();
+        assert_eq!(priorities[0], 100);
+        assert!(priorities.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(priorities.len(), 20);
+    }
+
+    #[test]
+    fn test_bucket_queue() {
+        let mut queue = BucketQueue::new();
+        queue.push(3, 'c');
+        queue.push(1, 'a');
+        queue.push(1, 'b');
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop(), Some((1, 'b')));
+        queue.push(1, 'd');
+        queue.push(2, 'e');
+        let rest: Vec<(usize, char)> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(rest, vec![(1, 'd'), (1, 'a'), (2, 'e'), (3, 'c')]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bucket_queue_not_monotone() {
+        let mut queue = BucketQueue::new();
+        queue.push(3, ());
+        queue.pop();
+        queue.push(2, ());
+    }
+}
@@ -0,0 +1,45 @@
+// Small deterministic pseudo-random number generator (xorshift64*) for
+// randomized algorithms and property tests. Not suitable for cryptography.
+#[derive(Debug, Clone)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        // The state must never be zero.
+        let state = seed ^ 0x9E37_79B9_7F4A_7C15;
+        Self {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // Uniform-ish value in `[0, n)`. The modulo bias is negligible for small `n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Empty range");
+        self.next_u64() % n
+    }
+
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+}
+
+#[test]
+fn test_xorshift_range() {
+    let mut rng = XorShift::new(0);
+    let mut seen = [false; 10];
+    for _ in 0..1000 {
+        seen[rng.below(10) as usize] = true;
+    }
+    assert!(seen.iter().all(|x| *x));
+}
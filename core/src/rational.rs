@@ -0,0 +1,244 @@
+use {
+    crate::num::{gcd, Integer},
+    std::{
+        cmp::Ordering,
+        fmt,
+        ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    },
+};
+
+// Signed integer types which can be used as the numerator and denominator of a `Rational`.
+pub trait SignedInteger: Integer + Neg<Output = Self> {}
+
+impl SignedInteger for i32 {}
+impl SignedInteger for i64 {}
+impl SignedInteger for i128 {}
+impl SignedInteger for isize {}
+
+// Exact fraction, always stored in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational<T> {
+    numerator: T,
+    denominator: T,
+}
+
+impl<T: SignedInteger> Rational<T> {
+    pub const ZERO: Self = Self {
+        numerator: T::ZERO,
+        denominator: T::ONE,
+    };
+    pub const ONE: Self = Self {
+        numerator: T::ONE,
+        denominator: T::ONE,
+    };
+
+    // Panics if the denominator is zero.
+    pub fn new(numerator: T, denominator: T) -> Self {
+        assert!(denominator != T::ZERO, "Zero denominator");
+        let d = gcd(numerator, denominator);
+        let (numerator, denominator) = if denominator < T::ZERO {
+            (-(numerator / d), -(denominator / d))
+        } else {
+            (numerator / d, denominator / d)
+        };
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub fn integer(x: T) -> Self {
+        Self {
+            numerator: x,
+            denominator: T::ONE,
+        }
+    }
+
+    pub fn numerator(&self) -> T {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> T {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == T::ZERO
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == T::ONE
+    }
+
+    // Returns `None` if the value is not a whole number.
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator,
+        }
+    }
+
+    // Panics if the value is zero.
+    pub fn recip(&self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+
+    // Rounds towards negative infinity.
+    pub fn floor(&self) -> T {
+        let q = self.numerator / self.denominator;
+        if self.numerator % self.denominator < T::ZERO {
+            q - T::ONE
+        } else {
+            q
+        }
+    }
+
+    pub fn ceil(&self) -> T {
+        -(-*self).floor()
+    }
+}
+
+impl<T: SignedInteger> Default for Rational<T> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<T: SignedInteger> From<T> for Rational<T> {
+    fn from(value: T) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl<T: SignedInteger> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl<T: SignedInteger> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: SignedInteger> Ord for Rational<T> {
+    // Denominators are positive so cross-multiplying preserves the order.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl<T: SignedInteger> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+// Arithmetic divides out common factors before multiplying
+// to keep intermediate values as small as possible.
+impl<T: SignedInteger> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let d = gcd(self.denominator, rhs.denominator);
+        let a = self.denominator / d;
+        let b = rhs.denominator / d;
+        Self::new(self.numerator * b + rhs.numerator * a, self.denominator * b)
+    }
+}
+
+impl<T: SignedInteger> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl<T: SignedInteger> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let d1 = gcd(self.numerator, rhs.denominator);
+        let d2 = gcd(rhs.numerator, self.denominator);
+        Self::new(
+            (self.numerator / d1) * (rhs.numerator / d2),
+            (self.denominator / d2) * (rhs.denominator / d1),
+        )
+    }
+}
+
+impl<T: SignedInteger> Div for Rational<T> {
+    type Output = Self;
+
+    // Panics when dividing by zero.
+    fn div(self, rhs: Self) -> Self::Output {
+        Mul::mul(self, rhs.recip())
+    }
+}
+
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<T: SignedInteger> $trait for Rational<T> {
+            fn $method(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_assign_op!(AddAssign, add_assign, +);
+impl_assign_op!(SubAssign, sub_assign, -);
+impl_assign_op!(MulAssign, mul_assign, *);
+impl_assign_op!(DivAssign, div_assign, /);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let x = Rational::new(6_i64, -4);
+        assert_eq!(x.numerator(), -3);
+        assert_eq!(x.denominator(), 2);
+        assert_eq!(x.to_string(), "-3/2");
+        assert_eq!(Rational::new(0_i64, -5), Rational::ZERO);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Rational::new(1_i128, 3);
+        let b = Rational::new(1_i128, 6);
+        assert_eq!(a + b, Rational::new(1, 2));
+        assert_eq!(a - b, Rational::new(1, 6));
+        assert_eq!(a * b, Rational::new(1, 18));
+        assert_eq!(a / b, Rational::integer(2));
+        assert!(b < a);
+        assert!(-a < b);
+    }
+
+    #[test]
+    fn test_floor_ceil() {
+        let x = Rational::new(-7_i64, 2);
+        assert_eq!(x.floor(), -4);
+        assert_eq!(x.ceil(), -3);
+        let y = Rational::new(7_i64, 2);
+        assert_eq!(y.floor(), 3);
+        assert_eq!(y.ceil(), 4);
+        assert_eq!(Rational::integer(5_i64).floor(), 5);
+    }
+}
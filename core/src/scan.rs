@@ -0,0 +1,308 @@
+use std::{fmt, str::FromStr};
+
+// Helpers for inputs which are mostly labels around numbers, e.g.
+// `Button A: X+94, Y+34` or `p=0,4 v=3,-3`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    // The text does not continue with the literal part of the pattern.
+    Literal { expected: String, offset: usize },
+    // The pattern has a different number of fields than the result type.
+    Arity { pattern: usize, result: usize },
+    // The field with this (0-based) index could not be parsed.
+    Field { index: usize, text: String },
+    // The text contains a different number of integers than requested.
+    Count { expected: usize, found: usize },
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal { expected, offset } => {
+                write!(f, "expected {expected:?} at byte {offset}")
+            }
+            Self::Arity { pattern, result } => {
+                write!(
+                    f,
+                    "pattern has {pattern} fields but {result} were requested"
+                )
+            }
+            Self::Field { index, text } => write!(f, "cannot parse field {index} from {text:?}"),
+            Self::Count { expected, found } => {
+                write!(f, "expected {expected} integers but found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+// The text of every integer in `text`. A `-` is part of the integer if it is
+// directly followed by a digit and does not follow a digit, so `x=-3..-1`
+// has `-3` and `-1` but `3-4` has `3` and `4`.
+pub fn integer_tokens(text: &str) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let start = i;
+            let negative = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+            if negative || bytes[i].is_ascii_digit() {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                return Some(&text[start..i]);
+            }
+            i += 1;
+        }
+        None
+    })
+}
+
+// Every integer in `text`, ignoring everything else.
+// Panics if one of them does not fit in `T`.
+pub fn integers<T: FromStr>(text: &str) -> impl Iterator<Item = T> + '_ {
+    integer_tokens(text).map(|s| s.parse().ok().expect("Integer fits in type"))
+}
+
+// Exactly `N` integers from `text`, e.g. `let [x, y] = integer_array(line)?`.
+pub fn integer_array<T: FromStr, const N: usize>(text: &str) -> Result<[T; N], ScanError> {
+    integer_fields(text)
+}
+
+// The integers of `text` as a tuple or array with one element per integer,
+// e.g. `let (id, size): (u32, usize) = integer_fields(line)?`.
+pub fn integer_fields<T: FromFields>(text: &str) -> Result<T, ScanError> {
+    let tokens: Vec<&str> = integer_tokens(text).collect();
+    if tokens.len() != T::ARITY {
+        return Err(ScanError::Count {
+            expected: T::ARITY,
+            found: tokens.len(),
+        });
+    }
+    T::from_fields(&tokens)
+}
+
+// Split `text` into the fields of `pattern`, where each `{}` in the pattern is
+// a field and everything else has to match exactly. A field extends up to the
+// next occurrence of the literal text which follows it in the pattern.
+pub fn fields<'t>(pattern: &str, text: &'t str) -> Result<Vec<&'t str>, ScanError> {
+    let mut literals = pattern.split("{}");
+    let first = literals.next().expect("Split has a first piece");
+    let literal_error = |expected: &str, rest: &str| ScanError::Literal {
+        expected: expected.to_string(),
+        offset: text.len() - rest.len(),
+    };
+    let mut rest = text
+        .strip_prefix(first)
+        .ok_or_else(|| literal_error(first, text))?;
+
+    let mut result = Vec::new();
+    let mut literals = literals.peekable();
+    while let Some(literal) = literals.next() {
+        let end = if literals.peek().is_none() && literal.is_empty() {
+            // The last field takes the rest of the text.
+            rest.len()
+        } else if literals.peek().is_none() {
+            // The pattern ends with a literal, which has to end the text.
+            rest.strip_suffix(literal)
+                .ok_or_else(|| literal_error(literal, rest))?
+                .len()
+        } else {
+            rest.find(literal)
+                .ok_or_else(|| literal_error(literal, rest))?
+        };
+        result.push(&rest[..end]);
+        rest = &rest[(end + literal.len())..];
+    }
+    Ok(result)
+}
+
+// Values which can be built from the fields of a pattern.
+pub trait FromFields: Sized {
+    const ARITY: usize;
+
+    fn from_fields(fields: &[&str]) -> Result<Self, ScanError>;
+}
+
+fn parse_field<T: FromStr>(fields: &[&str], index: usize) -> Result<T, ScanError> {
+    let text = fields[index];
+    text.trim().parse().map_err(|_| ScanError::Field {
+        index,
+        text: text.to_string(),
+    })
+}
+
+macro_rules! impl_from_fields {
+    ($n:literal; $($t:ident $i:literal),+) => {
+        impl<$($t: FromStr),+> FromFields for ($($t,)+) {
+            const ARITY: usize = $n;
+
+            fn from_fields(fields: &[&str]) -> Result<Self, ScanError> {
+                Ok(($(parse_field::<$t>(fields, $i)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_fields!(1; A 0);
+impl_from_fields!(2; A 0, B 1);
+impl_from_fields!(3; A 0, B 1, C 2);
+impl_from_fields!(4; A 0, B 1, C 2, D 3);
+impl_from_fields!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_fields!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_fields!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl<T: FromStr, const N: usize> FromFields for [T; N] {
+    const ARITY: usize = N;
+
+    fn from_fields(fields: &[&str]) -> Result<Self, ScanError> {
+        let values = (0..N)
+            .map(|i| parse_field(fields, i))
+            .collect::<Result<Vec<T>, _>>()?;
+        Ok(values.try_into().ok().expect("Exactly N fields"))
+    }
+}
+
+// Parse the fields of `pattern` from `text` into a tuple or array.
+pub fn scan<T: FromFields>(pattern: &str, text: &str) -> Result<T, ScanError> {
+    let fields = fields(pattern, text)?;
+    if fields.len() != T::ARITY {
+        return Err(ScanError::Arity {
+            pattern: fields.len(),
+            result: T::ARITY,
+        });
+    }
+    T::from_fields(&fields)
+}
+
+// Number of `{}` fields in a pattern, as split by `fields`.
+pub const fn count_fields(pattern: &str) -> usize {
+    let bytes = pattern.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            count += 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+// Like `scan`, but with the number of fields in the pattern known at compile
+// time, so a mismatch with the result type fails to build. Used by `scan!`.
+pub fn scan_fields<T: FromFields, const FIELDS: usize>(
+    pattern: &str,
+    text: &str,
+) -> Result<T, ScanError> {
+    const {
+        assert!(
+            FIELDS == T::ARITY,
+            "Pattern and result have different numbers of fields"
+        )
+    };
+    scan(pattern, text)
+}
+
+// `scan!("p={},{} v={},{}", line)` parses the fields of the pattern into a
+// tuple or array, whose types are usually inferred from the use site. The
+// fields are counted at compile time and must match the result's arity.
+#[macro_export]
+macro_rules! scan {
+    ($pattern:literal, $text:expr) => {
+        $crate::scan::scan_fields::<_, { $crate::scan::count_fields($pattern) }>($pattern, $text)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integers() {
+        let line = "Button A: X+94, Y-34 at x=-3..5, 3-4";
+        assert_eq!(
+            integers::<i64>(line).collect::<Vec<_>>(),
+            vec![94, -34, -3, 5, 3, 4]
+        );
+        assert_eq!(integer_array("Register A: 729"), Ok([729_u32]));
+        assert_eq!(
+            integer_array::<i32, 2>("1 2 3"),
+            Err(ScanError::Count {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            integer_array::<u8, 2>("1 -2"),
+            Err(ScanError::Field {
+                index: 1,
+                text: "-2".to_string()
+            })
+        );
+        let block = "Prize: X=8400, Y=5400\nButton B: X+22, Y+67";
+        let [x, y, a, b] = integer_array(block).unwrap();
+        assert_eq!((x, y, a, b), (8400_u64, 5400, 22, 67));
+
+        let (id, size): (u32, i64) = integer_fields("Disc #3 at -12").unwrap();
+        assert_eq!((id, size), (3, -12));
+        assert_eq!(
+            integer_fields::<(u8, u8, u8)>("1 2"),
+            Err(ScanError::Count {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        let (px, py, vx, vy): (i64, i64, i64, i64) =
+            scan!("p={},{} v={},{}", "p=0,4 v=3,-3").unwrap();
+        assert_eq!((px, py, vx, vy), (0, 4, 3, -3));
+
+        let (name, size): (String, usize) = scan!("{} ({} bytes)", "foo.txt (512 bytes)").unwrap();
+        assert_eq!((name.as_str(), size), ("foo.txt", 512));
+        let [a, b, c]: [u8; 3] = scan!("{}.{}.{}", "1.2.3").unwrap();
+        assert_eq!([a, b, c], [1, 2, 3]);
+
+        assert_eq!(
+            scan!("p={},{}", "q=1,2"),
+            Err::<(i32, i32), _>(ScanError::Literal {
+                expected: "p=".to_string(),
+                offset: 0
+            })
+        );
+        assert_eq!(
+            scan!("p={},{}", "p=1,x"),
+            Err::<(i32, i32), _>(ScanError::Field {
+                index: 1,
+                text: "x".to_string()
+            })
+        );
+        assert_eq!(count_fields("p={},{} v={},{}"), 4);
+        assert_eq!(count_fields("{}}{}"), 2);
+        // `scan!` rejects this at compile time, `scan` only when it runs.
+        assert_eq!(
+            scan("{}-{}", "1-2"),
+            Err::<(i32, i32, i32), _>(ScanError::Arity {
+                pattern: 2,
+                result: 3
+            })
+        );
+        assert_eq!(
+            scan!("({})", "(5"),
+            Err::<(i32,), _>(ScanError::Literal {
+                expected: ")".to_string(),
+                offset: 1
+            })
+        );
+    }
+}
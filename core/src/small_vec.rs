@@ -0,0 +1,370 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut},
+    slice, vec,
+};
+
+// Error returned by `SmallVec::try_push` when the inline storage is full.
+// Holds on to the value which could not be pushed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapacityError<T>(pub T);
+
+// Vector which stores up to `N` values inline and only moves them to the
+// heap once more than `N` are pushed.
+pub struct SmallVec<T, const N: usize> {
+    data: Data<T, N>,
+}
+
+enum Data<T, const N: usize> {
+    // The first `len` slots of `buf` are initialized.
+    Inline {
+        buf: [MaybeUninit<T>; N],
+        len: usize,
+    },
+    Heap(Vec<T>),
+}
+
+impl<T, const N: usize> Data<T, N> {
+    const EMPTY: Self = Self::Inline {
+        buf: [const { MaybeUninit::uninit() }; N],
+        len: 0,
+    };
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    pub const fn new() -> Self {
+        Self { data: Data::EMPTY }
+    }
+
+    // Whether the values have been moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline { .. } => N,
+            Data::Heap(v) => v.capacity(),
+        }
+    }
+
+    // Push without ever allocating. Fails if the inline storage is full.
+    pub fn try_push(&mut self, x: T) -> Result<(), CapacityError<T>> {
+        match &mut self.data {
+            Data::Inline { len, .. } if *len == N => Err(CapacityError(x)),
+            Data::Inline { buf, len } => {
+                buf[*len].write(x);
+                *len += 1;
+                Ok(())
+            }
+            Data::Heap(v) => {
+                v.push(x);
+                Ok(())
+            }
+        }
+    }
+
+    // Push, moving the values to the heap if the inline storage is full.
+    pub fn push(&mut self, x: T) {
+        if let Err(CapacityError(x)) = self.try_push(x) {
+            let mut v = Vec::with_capacity(2 * N + 1);
+            v.extend(mem::take(self));
+            v.push(x);
+            self.data = Data::Heap(v);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.data {
+            Data::Inline { len: 0, .. } => None,
+            Data::Inline { buf, len } => {
+                *len -= 1;
+                // SAFETY: slot `len` was initialized and is no longer counted.
+                Some(unsafe { buf[*len].assume_init_read() })
+            }
+            Data::Heap(v) => v.pop(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    // Keep only the values for which `f` returns true, preserving their order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        match &mut self.data {
+            Data::Inline { .. } => {
+                for x in mem::take(self) {
+                    if f(&x) {
+                        // At most as many values as before, so this never spills.
+                        self.push(x);
+                    }
+                }
+            }
+            Data::Heap(v) => v.retain(f),
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        if let Data::Inline { buf, len } = &mut self.data {
+            for slot in &mut buf[..*len] {
+                // SAFETY: the first `len` slots are initialized.
+                unsafe { slot.assume_init_drop() };
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match &self.data {
+            // SAFETY: the first `len` slots are initialized, and
+            // `MaybeUninit<T>` has the same layout as `T`.
+            Data::Inline { buf, len } => unsafe {
+                slice::from_raw_parts(buf.as_ptr().cast::<T>(), *len)
+            },
+            Data::Heap(v) => v,
+        }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match &mut self.data {
+            // SAFETY: as for `deref`.
+            Data::Inline { buf, len } => unsafe {
+                slice::from_raw_parts_mut(buf.as_mut_ptr().cast::<T>(), *len)
+            },
+            Data::Heap(v) => v,
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for SmallVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallVec<T, N> {}
+
+impl<T: Hash, const N: usize> Hash for SmallVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for SmallVec<T, N> {
+    fn from(value: [T; N]) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+// Owning iterator over the values of a `SmallVec`.
+pub struct IntoIter<T, const N: usize> {
+    inner: IntoIterInner<T, N>,
+}
+
+enum IntoIterInner<T, const N: usize> {
+    // The slots in `start..end` are initialized.
+    Inline {
+        buf: [MaybeUninit<T>; N],
+        start: usize,
+        end: usize,
+    },
+    Heap(vec::IntoIter<T>),
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match &mut self.inner {
+            IntoIterInner::Inline { buf, start, end } => {
+                if start == end {
+                    return None;
+                }
+                *start += 1;
+                // SAFETY: slot `start - 1` was initialized and is no longer in range.
+                Some(unsafe { buf[*start - 1].assume_init_read() })
+            }
+            IntoIterInner::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.inner {
+            IntoIterInner::Inline { start, end, .. } => end - start,
+            IntoIterInner::Heap(iter) => iter.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        match &mut self.inner {
+            IntoIterInner::Inline { buf, start, end } => {
+                if start == end {
+                    return None;
+                }
+                *end -= 1;
+                // SAFETY: slot `end` was initialized and is no longer in range.
+                Some(unsafe { buf[*end].assume_init_read() })
+            }
+            IntoIterInner::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        if let IntoIterInner::Inline { buf, start, end } = &mut self.inner {
+            for slot in &mut buf[*start..*end] {
+                // SAFETY: the slots in `start..end` are initialized.
+                unsafe { slot.assume_init_drop() };
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        // Leave an empty vector behind, so dropping `self` does nothing.
+        let inner = match mem::replace(&mut self.data, Data::EMPTY) {
+            Data::Inline { buf, len } => IntoIterInner::Inline {
+                buf,
+                start: 0,
+                end: len,
+            },
+            Data::Heap(v) => IntoIterInner::Heap(v.into_iter()),
+        };
+        IntoIter { inner }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::rc::Rc};
+
+    #[test]
+    fn test_push_and_spill() {
+        let mut v: SmallVec<u32, 3> = SmallVec::new();
+        assert!(v.is_empty());
+        v.push(1);
+        v.push(2);
+        assert_eq!(v.try_push(3), Ok(()));
+        assert_eq!(v.try_push(4), Err(CapacityError(4)));
+        assert!(!v.spilled());
+        assert_eq!(v.len(), 3);
+        v.push(4);
+        assert!(v.spilled());
+        assert_eq!(*v, [1, 2, 3, 4]);
+        assert_eq!(v.pop(), Some(4));
+        v[0] = 10;
+        assert_eq!(v.iter().sum::<u32>(), 15);
+
+        let w: SmallVec<u32, 2> = (0..5).collect();
+        assert_eq!(w.into_iter().rev().collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut v: SmallVec<u32, 8> = (0..8).collect();
+        v.retain(|x| x % 3 != 0);
+        assert_eq!(*v, [1, 2, 4, 5, 7]);
+        let mut v: SmallVec<u32, 2> = (0..8).collect();
+        v.retain(|x| x % 3 == 0);
+        assert_eq!(*v, [0, 3, 6]);
+    }
+
+    #[test]
+    fn test_drops() {
+        let counter = Rc::new(());
+        {
+            let mut v: SmallVec<Rc<()>, 3> = SmallVec::new();
+            v.extend((0..3).map(|_| counter.clone()));
+            assert_eq!(Rc::strong_count(&counter), 4);
+            drop(v.pop());
+            v.retain(|_| false);
+            assert_eq!(Rc::strong_count(&counter), 1);
+            v.extend((0..2).map(|_| counter.clone()));
+            let cloned = v.clone();
+            assert_eq!(cloned, v);
+            assert_eq!(Rc::strong_count(&counter), 5);
+            // Partially consumed iterators drop the rest of the values.
+            let mut iter = cloned.into_iter();
+            iter.next();
+            assert_eq!(Rc::strong_count(&counter), 4);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+        {
+            let _spilled: SmallVec<Rc<()>, 1> = (0..3).map(|_| counter.clone()).collect();
+            assert_eq!(Rc::strong_count(&counter), 4);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
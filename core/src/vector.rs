@@ -0,0 +1,313 @@
+use {
+    crate::{
+        abs_diff,
+        grid::{Position, SignedPosition},
+        num::Integer,
+    },
+    std::{
+        fmt,
+        ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+        str::FromStr,
+    },
+};
+
+// Error returned when parsing comma separated vector components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVectorError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+// Ordering is lexicographic in `(x, y, z)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Integer> Vec2<T> {
+    // The z component of the 3D cross product. Positive when `other` is
+    // counter-clockwise from `self`.
+    pub fn cross(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl<T: Integer> Vec3<T> {
+    pub fn cross(&self, other: &Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+// Operations shared by all vector types, defined component-wise.
+macro_rules! impl_vector {
+    ($name:ident { $($field:ident),+ }; $n:literal) => {
+        impl<T: Integer> $name<T> {
+            pub fn dot(&self, other: &Self) -> T {
+                T::ZERO $(+ self.$field * other.$field)+
+            }
+
+            // Sum of the absolute values of the components.
+            pub fn manhattan_norm(&self) -> T {
+                T::ZERO $(+ self.$field.abs())+
+            }
+
+            // Largest absolute value of the components.
+            pub fn chebyshev_norm(&self) -> T {
+                T::ZERO $(.max(self.$field.abs()))+
+            }
+
+            pub fn squared_norm(&self) -> T {
+                self.dot(self)
+            }
+
+            // The distance functions work for unsigned components too
+            // because they never form a negative intermediate value.
+            pub fn manhattan_distance(&self, other: &Self) -> T {
+                T::ZERO $(+ abs_diff(self.$field, other.$field))+
+            }
+
+            pub fn chebyshev_distance(&self, other: &Self) -> T {
+                T::ZERO $(.max(abs_diff(self.$field, other.$field)))+
+            }
+
+            pub fn squared_distance(&self, other: &Self) -> T {
+                T::ZERO $(+ abs_diff(self.$field, other.$field) * abs_diff(self.$field, other.$field))+
+            }
+        }
+
+        impl<T> $name<T> {
+            // Applies `f` to each component.
+            pub fn map<U, F: Fn(T) -> U>(self, f: F) -> $name<U> {
+                $name { $($field: f(self.$field)),+ }
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        // Scalar multiplication
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        // Scalar division, rounding each component the same way `T` does.
+        impl<T: Div<Output = T> + Copy> Div<T> for $name<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+
+        impl<T: MulAssign + Copy> MulAssign<T> for $name<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                $(self.$field *= rhs;)+
+            }
+        }
+
+        impl<T> From<[T; $n]> for $name<T> {
+            fn from(value: [T; $n]) -> Self {
+                let [$($field),+] = value;
+                Self { $($field),+ }
+            }
+        }
+
+        impl<T> From<$name<T>> for [T; $n] {
+            fn from(value: $name<T>) -> Self {
+                [$(value.$field),+]
+            }
+        }
+
+        // Parses comma separated components, e.g. `"1,2,3"`.
+        // Whitespace around each component is ignored.
+        impl<T: FromStr> FromStr for $name<T> {
+            type Err = ParseVectorError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut parts = s.split(',');
+                $(
+                    let $field = parts
+                        .next()
+                        .and_then(|p| p.trim().parse().ok())
+                        .ok_or(ParseVectorError)?;
+                )+
+                if parts.next().is_some() {
+                    return Err(ParseVectorError);
+                }
+                Ok(Self { $($field),+ })
+            }
+        }
+    };
+}
+
+impl_vector!(Vec2 { x, y }; 2);
+impl_vector!(Vec3 { x, y, z }; 3);
+
+impl<T> From<(T, T)> for Vec2<T> {
+    fn from(value: (T, T)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+impl<T> From<Vec2<T>> for (T, T) {
+    fn from(value: Vec2<T>) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Vec3<T> {
+    fn from(value: (T, T, T)) -> Self {
+        Self::new(value.0, value.1, value.2)
+    }
+}
+
+impl<T> From<Vec3<T>> for (T, T, T) {
+    fn from(value: Vec3<T>) -> Self {
+        (value.x, value.y, value.z)
+    }
+}
+
+// Conversions with grid positions keep the tuple order, so `x` is the row
+// and `y` is the column.
+impl Vec2<usize> {
+    pub fn signed(self) -> Vec2<isize> {
+        self.map(|c| c as isize)
+    }
+}
+
+impl Vec2<isize> {
+    // Returns `None` if either component is negative.
+    pub fn to_position(self) -> Option<Position> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+
+    pub fn to_signed_position(self) -> SignedPosition {
+        self.into()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Vec2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Vec3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vec3_products() {
+        let u = Vec3::new(1_i64, 2, 3);
+        let v = Vec3::new(4, 5, 6);
+        assert_eq!(u + v, Vec3::new(5, 7, 9));
+        assert_eq!(v - u, Vec3::new(3, 3, 3));
+        assert_eq!(u * 2, Vec3::new(2, 4, 6));
+        assert_eq!(-u, Vec3::new(-1, -2, -3));
+        assert_eq!(u.dot(&v), 32);
+        let w = u.cross(&v);
+        assert_eq!(w, Vec3::new(-3, 6, -3));
+        assert_eq!(w.dot(&u), 0);
+        assert_eq!(w.dot(&v), 0);
+    }
+
+    #[test]
+    fn test_norms() {
+        let u = Vec2::new(-3_i32, 4);
+        assert_eq!(u.manhattan_norm(), 7);
+        assert_eq!(u.chebyshev_norm(), 4);
+        assert_eq!(u.squared_norm(), 25);
+        assert_eq!(Vec2::new(1, 0).cross(&Vec2::new(0, 1)), 1);
+
+        // Unsigned distances do not underflow
+        let a = Vec3::new(162_u64, 817, 812);
+        let b = Vec3::new(425, 690, 689);
+        assert_eq!(a.squared_distance(&b), 263 * 263 + 127 * 127 + 123 * 123);
+        assert_eq!(a.manhattan_distance(&b), 263 + 127 + 123);
+        assert_eq!(b.chebyshev_distance(&a), 263);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("1,2,3".parse(), Ok(Vec3::new(1_u8, 2, 3)));
+        assert_eq!("19, 13, -30".parse(), Ok(Vec3::new(19_i64, 13, -30)));
+        assert_eq!("-5,7".parse(), Ok(Vec2::new(-5_i64, 7)));
+        assert_eq!("1,2".parse::<Vec3<i64>>(), Err(ParseVectorError));
+        assert_eq!("1,2,3".parse::<Vec2<i64>>(), Err(ParseVectorError));
+        assert_eq!("1,x".parse::<Vec2<i64>>(), Err(ParseVectorError));
+        assert_eq!(Vec3::new(1, -2, 3).to_string(), "1,-2,3");
+    }
+
+    #[test]
+    fn test_position_conversion() {
+        let p: Position = (2, 5);
+        let v = Vec2::from(p).signed() + Vec2::new(-2, 1);
+        assert_eq!(v.to_position(), Some((0, 6)));
+        assert_eq!((v - Vec2::new(1, 0)).to_position(), None);
+        assert_eq!(v.to_signed_position(), (0, 6));
+    }
+}